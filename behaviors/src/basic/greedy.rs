@@ -0,0 +1,48 @@
+use gemstone::*;
+
+/// A behavior which bids the full value of the most valuable card on the
+/// stack, as given by the [`BidValuation`].
+#[derive(Debug, Default)]
+pub struct GreedyBehavior {
+    weights: ValuationWeights,
+}
+
+impl GreedyBehavior {
+    pub fn new(weights: ValuationWeights) -> Self {
+        Self { weights }
+    }
+}
+
+impl PlayerBehavior for GreedyBehavior {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        let max_bid = BidValuation::new(info)
+            .with_weights(self.weights)
+            .max_bid(info.current_player());
+        match max_bid > info.highest_bid() {
+            true => max_bid,
+            false => 0,
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        let player = info.current_player();
+        let card_idx = BidValuation::new(info)
+            .with_weights(self.weights)
+            .best_card(player)
+            .unwrap_or(0);
+        let payment = PaymentOptimizer::new(info.my_inventory())
+            .best(
+                info.highest_bid(),
+                &ProtectMajorities::from_info(info, player),
+            )
+            .map(|payment| payment.choice)
+            .unwrap_or(CardChoice::NONE);
+        (card_idx, payment)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        ReinvestmentPlanner::new(info, info.current_player())
+            .best()
+            .choice
+    }
+}
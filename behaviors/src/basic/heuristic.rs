@@ -0,0 +1,106 @@
+use gemstone::*;
+
+/// The parameters of the [`HeuristicBehavior`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeuristicParams {
+    /// The value of a single non-leveraged gem.
+    pub gem_value: f32,
+    /// The value of winning the sole majority of a gem type, where a shared
+    /// majority is worth two thirds of it.
+    pub majority_bonus: f32,
+    /// The capital kept out of bids for each remaining round, which is also
+    /// the value of a unit of capital per remaining round when reinvesting.
+    pub capital_reserve: f32,
+    /// The number of capital units bid for each unit of card value.
+    pub bid_rate: f32,
+}
+
+impl Default for HeuristicParams {
+    fn default() -> Self {
+        Self {
+            gem_value: 1.0,
+            majority_bonus: 3.0,
+            capital_reserve: 0.25,
+            bid_rate: 1.0,
+        }
+    }
+}
+
+impl HeuristicParams {
+    /// Returns the weights used to value cards on the stack.
+    pub fn valuation(&self) -> ValuationWeights {
+        ValuationWeights {
+            gem: self.gem_value,
+            sole_majority: self.majority_bonus,
+            shared_majority: self.majority_bonus * 2.0 / 3.0,
+            exchange_rate: self.bid_rate,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the weights used to plan reinvestments.
+    pub fn reinvestment(&self) -> ReinvestmentWeights {
+        ReinvestmentWeights {
+            point: self.gem_value,
+            liquidity: self.capital_reserve,
+        }
+    }
+}
+
+/// A behavior like the [`GreedyBehavior`](super::GreedyBehavior), but with
+/// a small set of parameters meant to be tuned, see [`HeuristicParams`].
+#[derive(Clone, Debug, Default)]
+pub struct HeuristicBehavior {
+    params: HeuristicParams,
+}
+
+impl HeuristicBehavior {
+    pub fn new(params: HeuristicParams) -> Self {
+        Self { params }
+    }
+
+    #[inline]
+    pub fn params(&self) -> &HeuristicParams {
+        &self.params
+    }
+}
+
+impl PlayerBehavior for HeuristicBehavior {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        let player = info.current_player();
+        let max_bid = BidValuation::new(info)
+            .with_weights(self.params.valuation())
+            .max_bid(player);
+
+        let remaining = 5_usize.saturating_sub(info.round_index());
+        let reserve = (self.params.capital_reserve * remaining as f32).round() as BidValue;
+        let bid = max_bid.min(info.inventory_at(player).iter().capital() - reserve.max(0));
+        match bid > info.highest_bid() {
+            true => bid,
+            false => 0,
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        let player = info.current_player();
+        let card_idx = BidValuation::new(info)
+            .with_weights(self.params.valuation())
+            .best_card(player)
+            .unwrap_or(0);
+        let payment = PaymentOptimizer::new(info.my_inventory())
+            .best(
+                info.highest_bid(),
+                &ProtectMajorities::from_info(info, player),
+            )
+            .map(|payment| payment.choice)
+            .unwrap_or(CardChoice::NONE);
+        (card_idx, payment)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        ReinvestmentPlanner::new(info, info.current_player())
+            .with_weights(self.params.reinvestment())
+            .best()
+            .choice
+    }
+}
@@ -1,20 +1,7 @@
-// use gemstone::{GameQuery, PlayerBehavior};
+mod greedy;
+mod heuristic;
+mod random;
 
-// #[derive(Debug, Default)]
-// pub struct BasicAi;
-
-// impl PlayerBehavior for BasicAi {
-//     fn bid(&mut self, _query: GameQuery) -> BidValue {
-//         0
-//     }
-
-//     fn pick_card(&mut self, _query: GameQuery) -> (usize, Vec<usize>) {
-//         println!("ai::pick_card");
-//         (0, vec![])
-//     }
-
-//     fn reinvest(&mut self, _query: GameQuery) -> (Vec<usize>, Vec<usize>) {
-//         println!("ai::reinvest");
-//         (vec![], vec![])
-//     }
-// }
+pub use greedy::GreedyBehavior;
+pub use heuristic::{HeuristicBehavior, HeuristicParams};
+pub use random::RandomBehavior;
@@ -0,0 +1,55 @@
+use gemstone::*;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+/// A behavior which makes a uniformly random choice among the legal moves of
+/// the [`Action`] space, useful as a baseline opponent.
+pub struct RandomBehavior {
+    rng: StdRng,
+}
+
+impl Default for RandomBehavior {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl RandomBehavior {
+    /// Creates a behavior making the same choices for every game with the
+    /// same seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn choose(&mut self, info: &GameInfo) -> Option<Move> {
+        Action::legal_moves(info)
+            .choose(&mut self.rng)
+            .map(|&(_, next)| next)
+    }
+}
+
+impl PlayerBehavior for RandomBehavior {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        match self.choose(info) {
+            Some(Move::Bid(bid)) => bid,
+            _ => 0,
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        match self.choose(info) {
+            Some(Move::Pick(slot, payment)) => (slot, payment),
+            _ => (0, CardChoice::NONE),
+        }
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        match self.choose(info) {
+            Some(Move::Flip(flipped)) => flipped,
+            _ => CardChoice::NONE,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use gemstone::*;
+
+/// The abstract actions available to a player in the auction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuctionAction {
+    /// Bid nothing.
+    Pass,
+    /// Bid one more than the current highest bid.
+    Min,
+    /// Bid halfway between the minimum raise and all capital.
+    Mid,
+    /// Bid all capital.
+    Max,
+}
+
+impl AuctionAction {
+    pub const COUNT: usize = 4;
+    pub const ALL: [AuctionAction; Self::COUNT] = [Self::Pass, Self::Min, Self::Mid, Self::Max];
+
+    /// Returns whether this action is available given the highest bid and
+    /// the capital of the player.
+    pub fn is_legal(self, highest_bid: BidValue, capital: BidValue) -> bool {
+        match self {
+            Self::Pass => true,
+            _ => Self::min_raise(highest_bid) <= capital,
+        }
+    }
+
+    /// Converts this action into an actual bid.
+    pub fn to_bid(self, highest_bid: BidValue, capital: BidValue) -> BidValue {
+        let min = Self::min_raise(highest_bid);
+        match self {
+            Self::Pass => 0,
+            Self::Min => min,
+            Self::Mid => min + (capital - min) / 2,
+            Self::Max => capital,
+        }
+    }
+
+    fn min_raise(highest_bid: BidValue) -> BidValue {
+        (highest_bid + 1).max(1)
+    }
+}
+
+/// The abstraction of a single auction decision, which identifies an
+/// information set of the [`CfrTrainer`](super::CfrTrainer). Each field is a
+/// bucket in range `[0..16)`. The contents of the stack are reduced to the
+/// value of its most valuable card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InfoSetKey {
+    /// The value of the most valuable card on the stack.
+    pub stack: u8,
+    /// The number of gem types where the player holds the potential majority.
+    pub majority: u8,
+    /// The capital of the player.
+    pub capital: u8,
+    /// The highest bid made so far, or zero if no bids have been made.
+    pub bid: u8,
+}
+
+impl InfoSetKey {
+    /// Creates a key from raw values, putting each value into its bucket.
+    pub fn new(
+        stack_value: f32,
+        majorities: usize,
+        capital: BidValue,
+        highest_bid: BidValue,
+    ) -> Self {
+        Self {
+            stack: (stack_value.max(0.0).round() as u8).min(7),
+            majority: majorities.min(3) as u8,
+            capital: (capital.max(0) / 2).min(6) as u8,
+            bid: ((highest_bid + 1).max(0) as u8).div_ceil(2).min(6),
+        }
+    }
+
+    /// Creates the key of the current player in an auction.
+    pub fn from_info(info: &GameInfo) -> Self {
+        let (stack_value, majorities, capital) = Self::features(info, info.current_player());
+        Self::new(stack_value, majorities, capital, info.highest_bid())
+    }
+
+    /// Returns the raw values bucketed by [`new`](Self::new) for any player:
+    /// the value of the most valuable card on the stack, the number of
+    /// potential majorities and the capital.
+    pub(super) fn features(info: &GameInfo, player: usize) -> (f32, usize, BidValue) {
+        let valuation = BidValuation::new(info);
+        let stack_value = valuation
+            .values(player)
+            .iter()
+            .map(|value| value.total(valuation.weights()))
+            .fold(0.0, f32::max);
+        let majorities = GemType::iter()
+            .filter(|&gem| info.majorities().potential_majority(gem).holds(player))
+            .count();
+        let capital = info.inventory_at(player).iter().capital();
+        (stack_value, majorities, capital)
+    }
+
+    /// Packs the key into a single integer.
+    pub fn pack(self) -> u32 {
+        self.stack as u32
+            | (self.majority as u32) << 4
+            | (self.capital as u32) << 8
+            | (self.bid as u32) << 12
+    }
+
+    /// Unpacks a key created by [`pack`](Self::pack).
+    pub fn unpack(key: u32) -> Self {
+        Self {
+            stack: (key & 0xf) as u8,
+            majority: (key >> 4 & 0xf) as u8,
+            capital: (key >> 8 & 0xf) as u8,
+            bid: (key >> 12 & 0xf) as u8,
+        }
+    }
+}
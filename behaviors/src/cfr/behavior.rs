@@ -0,0 +1,72 @@
+use std::{io, path::Path};
+
+use gemstone::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::{trainer::sample, AuctionAction, InfoSetKey, StrategyTable};
+use crate::GreedyBehavior;
+
+/// A behavior which bids according to a [`StrategyTable`] trained by the
+/// [`CfrTrainer`](super::CfrTrainer). Information sets missing from the table
+/// are played uniformly at random, and all decisions besides bidding are
+/// delegated to the [`GreedyBehavior`].
+pub struct CfrBehavior {
+    table: StrategyTable,
+    rng: StdRng,
+    fallback: GreedyBehavior,
+}
+
+impl Default for CfrBehavior {
+    fn default() -> Self {
+        Self::new(StrategyTable::default())
+    }
+}
+
+impl CfrBehavior {
+    pub fn new(table: StrategyTable) -> Self {
+        Self {
+            table,
+            rng: StdRng::from_entropy(),
+            fallback: GreedyBehavior::default(),
+        }
+    }
+
+    /// Creates a behavior from a strategy table saved to a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(StrategyTable::load(path)?))
+    }
+}
+
+impl PlayerBehavior for CfrBehavior {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        let capital = info.my_inventory().iter().capital();
+        let legal = AuctionAction::ALL.map(|action| action.is_legal(info.highest_bid(), capital));
+
+        let key = InfoSetKey::from_info(info);
+        let mut probabilities = match self.table.get(key) {
+            Some(probabilities) => probabilities.map(|p| p as f64),
+            None => [1.0; AuctionAction::COUNT],
+        };
+        for i in 0..AuctionAction::COUNT {
+            if !legal[i] {
+                probabilities[i] = 0.0;
+            }
+        }
+        let sum = probabilities.iter().sum::<f64>();
+        if sum <= 0.0 {
+            return 0;
+        }
+        probabilities.iter_mut().for_each(|p| *p /= sum);
+
+        let action = AuctionAction::ALL[sample(&probabilities, &mut self.rng)];
+        action.to_bid(info.highest_bid(), capital)
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        self.fallback.pick_card(info)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        self.fallback.reinvest(info)
+    }
+}
@@ -0,0 +1,9 @@
+mod abstraction;
+mod behavior;
+mod strategy;
+mod trainer;
+
+pub use abstraction::{AuctionAction, InfoSetKey};
+pub use behavior::CfrBehavior;
+pub use strategy::StrategyTable;
+pub use trainer::CfrTrainer;
@@ -0,0 +1,119 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+use super::{AuctionAction, InfoSetKey};
+
+const HEADER: &str = "# gem cfr strategy v1";
+
+/// The average strategy produced by the [`CfrTrainer`](super::CfrTrainer),
+/// mapping each information set to a probability for each [`AuctionAction`].
+#[derive(Clone, Debug, Default)]
+pub struct StrategyTable {
+    entries: HashMap<u32, [f32; AuctionAction::COUNT]>,
+}
+
+impl StrategyTable {
+    pub fn insert(&mut self, key: InfoSetKey, probabilities: [f32; AuctionAction::COUNT]) {
+        self.entries.insert(key.pack(), probabilities);
+    }
+
+    /// Returns the action probabilities of an information set, if it was
+    /// visited during training.
+    pub fn get(&self, key: InfoSetKey) -> Option<&[f32; AuctionAction::COUNT]> {
+        self.entries.get(&key.pack())
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Saves the table as text, with one information set per line.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut keys = self.entries.keys().collect::<Vec<_>>();
+        keys.sort();
+
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "{HEADER}")?;
+        for key in keys {
+            let probabilities = self.entries[key]
+                .iter()
+                .map(|p| format!("{p:.4}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(file, "{key:04x} {probabilities}")?;
+        }
+        file.flush()
+    }
+
+    /// Loads a table previously written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid strategy line: {line}"),
+            )
+        };
+
+        let mut table = Self::default();
+        for line in BufReader::new(fs::File::open(path)?).lines() {
+            let line = line?;
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let key = parts
+                .next()
+                .and_then(|key| u32::from_str_radix(key, 16).ok())
+                .ok_or_else(|| invalid(&line))?;
+            let mut probabilities = [0.0; AuctionAction::COUNT];
+            for p in probabilities.iter_mut() {
+                *p = parts
+                    .next()
+                    .and_then(|p| p.parse().ok())
+                    .ok_or_else(|| invalid(&line))?;
+            }
+            table.entries.insert(key, probabilities);
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::CfrTrainer;
+
+    #[test]
+    fn saved_tables_load_back() {
+        let mut trainer = CfrTrainer::new(3);
+        trainer.train(500, &mut StdRng::seed_from_u64(3));
+        let table = trainer.strategy();
+        assert!(!table.is_empty());
+
+        let path = std::env::temp_dir().join(format!("gem-cfr-{}.txt", std::process::id()));
+        table.save(&path).unwrap();
+        let loaded = StrategyTable::load(&path);
+        fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.len(), table.len());
+        for (key, probabilities) in &table.entries {
+            let restored = &loaded.entries[key];
+            for (p, q) in probabilities.iter().zip(restored) {
+                assert!((p - q).abs() <= 1e-4, "{p} was restored as {q}");
+            }
+        }
+    }
+}
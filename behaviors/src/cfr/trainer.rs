@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use gemstone::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::{AuctionAction, InfoSetKey, StrategyTable};
+use crate::GreedyBehavior;
+
+/// The accumulated regrets and strategies of a single information set.
+#[derive(Clone, Default)]
+struct Node {
+    regret: [f64; AuctionAction::COUNT],
+    strategy_sum: [f64; AuctionAction::COUNT],
+}
+
+impl Node {
+    /// Returns the current strategy using regret matching.
+    fn strategy(&self, legal: &[bool; AuctionAction::COUNT]) -> [f64; AuctionAction::COUNT] {
+        let mut strategy = [0.0; AuctionAction::COUNT];
+        for i in 0..AuctionAction::COUNT {
+            if legal[i] {
+                strategy[i] = self.regret[i].max(0.0);
+            }
+        }
+        normalize(&mut strategy, legal);
+        strategy
+    }
+}
+
+/// A single sampled auction, as seen by the trainer.
+struct Deal {
+    /// The value of the most valuable card on the stack to each player.
+    values: Vec<f32>,
+    /// The number of potential majorities held by each player.
+    majorities: Vec<usize>,
+    /// The capital of each player.
+    capital: Vec<BidValue>,
+}
+
+/// Trains a bidding strategy using external sampling Monte Carlo
+/// counterfactual regret minimisation (MCCFR).
+///
+/// The trainer does not play actual games, but works on an abstraction of a
+/// single auction cycle where each player bids once in turn, and the highest
+/// bidder buys the most valuable card. Every iteration starts from the first
+/// bid of an auction in a seeded game between [`GreedyBehavior`]s, whose
+/// values, majorities and capital are computed as by
+/// [`InfoSetKey::from_info`], such that training visits the same information
+/// sets as actual play.
+pub struct CfrTrainer {
+    num_players: usize,
+    /// The cost of a single unit of capital spent on a bid, relative to the
+    /// value of a card.
+    capital_cost: f32,
+    nodes: HashMap<u32, Node>,
+    /// Positions at the start of an auction, which are not yet trained on.
+    positions: Vec<GameInfo>,
+}
+
+impl CfrTrainer {
+    pub fn new(num_players: usize) -> Self {
+        assert!((2..=4).contains(&num_players));
+        Self {
+            num_players,
+            capital_cost: 0.5,
+            nodes: HashMap::new(),
+            positions: Vec::new(),
+        }
+    }
+
+    pub fn with_capital_cost(mut self, capital_cost: f32) -> Self {
+        self.capital_cost = capital_cost;
+        self
+    }
+
+    /// Runs the given number of training iterations, where each iteration
+    /// samples a single auction and traverses it once for every player.
+    pub fn train(&mut self, iterations: usize, rng: &mut impl Rng) {
+        for _ in 0..iterations {
+            let deal = self.deal(rng);
+            for player in 0..self.num_players {
+                self.traverse(&deal, player, 0, -1, self.num_players - 1, rng);
+            }
+        }
+    }
+
+    /// Returns the average strategy of every visited information set.
+    pub fn strategy(&self) -> StrategyTable {
+        let mut table = StrategyTable::default();
+        for (&key, node) in &self.nodes {
+            let mut strategy = node.strategy_sum;
+            normalize(&mut strategy, &[true; AuctionAction::COUNT]);
+            table.insert(InfoSetKey::unpack(key), strategy.map(|p| p as f32));
+        }
+        table
+    }
+
+    /// Takes the next position at the start of an auction, playing a new
+    /// seeded game once every position was used.
+    fn deal(&mut self, rng: &mut impl Rng) -> Deal {
+        while self.positions.is_empty() {
+            self.positions = Self::auction_starts(self.num_players, rng);
+            self.positions.shuffle(rng);
+        }
+        Self::deal_from(&self.positions.pop().unwrap())
+    }
+
+    /// Returns every position of a game between greedy bots where the first
+    /// bid of an auction is to be made.
+    fn auction_starts(num_players: usize, rng: &mut impl Rng) -> Vec<GameInfo> {
+        let behaviors = (0..num_players)
+            .map(|_| Box::new(GreedyBehavior::default()) as Box<dyn PlayerBehavior>)
+            .collect();
+        let mut game = Game::with_rng(behaviors, &mut StdRng::seed_from_u64(rng.gen()));
+        let mut positions = Vec::new();
+        while !game.info_ref().game_over() {
+            let info = game.info_ref();
+            if matches!(info.next_decision(), Some((_, Decision::Bid))) && info.highest_bid() < 0 {
+                positions.push(info.clone());
+            }
+            if game.step().is_err() {
+                break;
+            }
+        }
+        positions
+    }
+
+    /// Describes the auction of a position, where turn zero is the current
+    /// player and the turns follow the seats clockwise.
+    fn deal_from(info: &GameInfo) -> Deal {
+        let num_players = info.num_players();
+        let mut deal = Deal {
+            values: Vec::with_capacity(num_players),
+            majorities: Vec::with_capacity(num_players),
+            capital: Vec::with_capacity(num_players),
+        };
+        for turn in 0..num_players {
+            let player = (info.current_player() + turn) % num_players;
+            let (value, majorities, capital) = InfoSetKey::features(info, player);
+            deal.values.push(value);
+            deal.majorities.push(majorities);
+            deal.capital.push(capital);
+        }
+        deal
+    }
+
+    fn traverse(
+        &mut self,
+        deal: &Deal,
+        updating: usize,
+        turn: usize,
+        highest_bid: BidValue,
+        highest_bidder: usize,
+        rng: &mut impl Rng,
+    ) -> f64 {
+        if turn == self.num_players {
+            return self.utility(deal, updating, highest_bid, highest_bidder);
+        }
+
+        let capital = deal.capital[turn];
+        let key = InfoSetKey::new(
+            deal.values[turn],
+            deal.majorities[turn],
+            capital,
+            highest_bid,
+        )
+        .pack();
+        let legal = AuctionAction::ALL.map(|action| action.is_legal(highest_bid, capital));
+        let strategy = self.nodes.entry(key).or_default().strategy(&legal);
+
+        let next = |action: AuctionAction| {
+            let bid = action.to_bid(highest_bid, capital);
+            match bid > highest_bid {
+                true => (bid, turn),
+                false => (highest_bid, highest_bidder),
+            }
+        };
+
+        if turn == updating {
+            let mut utilities = [0.0; AuctionAction::COUNT];
+            let mut expected = 0.0;
+            for (i, &action) in AuctionAction::ALL.iter().enumerate() {
+                if !legal[i] {
+                    continue;
+                }
+                let (bid, bidder) = next(action);
+                utilities[i] = self.traverse(deal, updating, turn + 1, bid, bidder, rng);
+                expected += strategy[i] * utilities[i];
+            }
+            let node = self.nodes.get_mut(&key).unwrap();
+            for i in 0..AuctionAction::COUNT {
+                if legal[i] {
+                    node.regret[i] += utilities[i] - expected;
+                }
+            }
+            expected
+        } else {
+            let node = self.nodes.get_mut(&key).unwrap();
+            for (sum, p) in node.strategy_sum.iter_mut().zip(strategy) {
+                *sum += p;
+            }
+            let action = AuctionAction::ALL[sample(&strategy, rng)];
+            let (bid, bidder) = next(action);
+            self.traverse(deal, updating, turn + 1, bid, bidder, rng)
+        }
+    }
+
+    /// Returns the gain of the player relative to the average gain of the
+    /// other players, where only the highest bidder gains anything.
+    fn utility(&self, deal: &Deal, player: usize, bid: BidValue, bidder: usize) -> f64 {
+        let gain = (deal.values[bidder] - bid as f32 * self.capital_cost) as f64;
+        match player == bidder {
+            true => gain,
+            false => -gain / (self.num_players - 1) as f64,
+        }
+    }
+}
+
+fn normalize(strategy: &mut [f64; AuctionAction::COUNT], legal: &[bool; AuctionAction::COUNT]) {
+    let sum = strategy.iter().sum::<f64>();
+    if sum > 0.0 {
+        strategy.iter_mut().for_each(|p| *p /= sum);
+    } else {
+        let count = legal.iter().filter(|&&legal| legal).count() as f64;
+        for i in 0..AuctionAction::COUNT {
+            strategy[i] = if legal[i] { 1.0 / count } else { 0.0 };
+        }
+    }
+}
+
+/// Samples an index from a probability distribution.
+pub(crate) fn sample(probabilities: &[f64], rng: &mut impl Rng) -> usize {
+    let mut target = rng.gen::<f64>();
+    for (i, &p) in probabilities.iter().enumerate() {
+        if target < p {
+            return i;
+        }
+        target -= p;
+    }
+    probabilities.iter().rposition(|&p| p > 0.0).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn deals_match_the_keys_of_actual_play() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for num_players in 2..=4 {
+            let positions = CfrTrainer::auction_starts(num_players, &mut rng);
+            assert!(!positions.is_empty());
+            for info in &positions {
+                let deal = CfrTrainer::deal_from(info);
+                let mut info = info.clone();
+                for turn in 0..num_players {
+                    let key = InfoSetKey::new(
+                        deal.values[turn],
+                        deal.majorities[turn],
+                        deal.capital[turn],
+                        info.highest_bid(),
+                    );
+                    assert_eq!(key, InfoSetKey::from_info(&info));
+                    let next = info.next_clockwise_player(info.current_player());
+                    info.set_current_player(next);
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,13 @@
-// mod basic;
+mod basic;
+mod cfr;
+mod modelling;
+mod neural;
+mod registry;
+mod tuning;
 
-// pub use basic::BasicAi;
+pub use basic::*;
+pub use cfr::*;
+pub use modelling::*;
+pub use neural::*;
+pub use registry::register;
+pub use tuning::*;
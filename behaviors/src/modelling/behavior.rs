@@ -0,0 +1,142 @@
+use gemstone::*;
+
+use super::SharedModel;
+use crate::{HeuristicBehavior, HeuristicParams};
+
+/// A behavior which models the bidding tendencies of its opponents and shades
+/// its bids to just above what the remaining bidders are expected to bid.
+///
+/// The highest bid it is willing to make is that of the [`HeuristicBehavior`],
+/// which also makes every decision besides bidding. Creating every behavior
+/// of a match from the same [`SharedModel`] keeps the model across games, in
+/// which case the identity of the opponent at each seat should be set with
+/// [`with_opponents`](Self::with_opponents).
+pub struct ModellingBehavior {
+    model: SharedModel,
+    fallback: HeuristicBehavior,
+    /// The identity of the player at each seat.
+    opponents: Vec<String>,
+    /// The number of events in the history already observed.
+    seen: usize,
+}
+
+impl Default for ModellingBehavior {
+    fn default() -> Self {
+        Self::new(SharedModel::default())
+    }
+}
+
+impl ModellingBehavior {
+    pub fn new(model: SharedModel) -> Self {
+        Self {
+            model,
+            fallback: HeuristicBehavior::default(),
+            opponents: (0..4).map(|seat| format!("seat{seat}")).collect(),
+            seen: 0,
+        }
+    }
+
+    pub fn with_params(mut self, params: HeuristicParams) -> Self {
+        self.fallback = HeuristicBehavior::new(params);
+        self
+    }
+
+    /// Sets the identity of the player at each seat, which keys the profiles
+    /// of the model. Seats without an identity are modelled as `seat<n>`.
+    pub fn with_opponents(mut self, opponents: &[&str]) -> Self {
+        for (seat, opponent) in opponents.iter().take(4).enumerate() {
+            self.opponents[seat] = opponent.to_string();
+        }
+        self
+    }
+
+    #[inline]
+    pub fn model(&self) -> &SharedModel {
+        &self.model
+    }
+
+    /// Observes every event since the previous decision.
+    fn update(&mut self, info: &GameInfo) {
+        // the behavior is used for a new game
+        if info.history().len() < self.seen {
+            self.seen = 0;
+        }
+        self.model
+            .lock()
+            .unwrap()
+            .observe(info, self.seen, info.current_player(), &self.opponents);
+        self.seen = info.history().len();
+    }
+}
+
+impl PlayerBehavior for ModellingBehavior {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        self.update(info);
+        let limit = self.fallback.bid(info);
+        if limit <= info.highest_bid() {
+            return 0;
+        }
+
+        let value = info
+            .stack()
+            .iter()
+            .map(|card| card.value())
+            .max()
+            .unwrap_or(0);
+        let model = self.model.lock().unwrap();
+        // the players who still bid after this player in the current cycle
+        let mut competition = info.highest_bid();
+        let mut seat = info.next_clockwise_player(info.current_player());
+        while seat != info.starting_player() {
+            let capital = info.inventory_at(seat).iter().capital();
+            let profile = model.profile(&self.opponents[seat]);
+            competition = competition.max(profile.predict_bid(value, capital));
+            seat = info.next_clockwise_player(seat);
+        }
+
+        match competition < limit {
+            true => competition + 1,
+            // the opponents may still pass, so bid the limit anyway
+            false => limit,
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        self.update(info);
+        self.fallback.pick_card(info)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        self.update(info);
+        self.fallback.reinvest(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_follow_opponents_across_seats() {
+        let model = SharedModel::default();
+        for opponents in [["bold", "shy", "me"], ["shy", "bold", "me"]] {
+            let mut info = GameInfo::with_deck(3, Card::gem_deck());
+            info.prepare_auction();
+            let bold = opponents.iter().position(|&name| name == "bold").unwrap();
+            for seat in 0..2 {
+                info.apply_bid(if seat == bold { 3 } else { 0 }).unwrap();
+            }
+
+            let mut behavior = ModellingBehavior::new(model.clone()).with_opponents(&opponents);
+            behavior.bid(&info);
+        }
+
+        let model = model.lock().unwrap();
+        let (bold, shy) = (model.profile("bold"), model.profile("shy"));
+        assert_eq!((bold.raises, bold.passes), (2, 0));
+        // opening the auction with a zero bid still counts as a raise
+        assert_eq!((shy.raises, shy.passes), (1, 1));
+        assert!(bold.value_ratio() > shy.value_ratio());
+        assert_eq!(model.profile("seat0").raises, 0);
+    }
+}
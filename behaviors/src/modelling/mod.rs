@@ -0,0 +1,5 @@
+mod behavior;
+mod model;
+
+pub use behavior::ModellingBehavior;
+pub use model::{BidderProfile, OpponentModel, SharedModel};
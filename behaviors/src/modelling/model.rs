@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use gemstone::*;
+
+/// An [`OpponentModel`] shared between the behaviors of a match, such that
+/// the observations of earlier games carry over to later games.
+pub type SharedModel = Arc<Mutex<OpponentModel>>;
+
+/// The observed bidding tendencies of a single opponent. Until enough bids have
+/// been observed, each ratio is blended with a prior.
+#[derive(Clone, Debug, Default)]
+pub struct BidderProfile {
+    /// The number of bids exceeding the highest bid.
+    pub raises: u32,
+    /// The number of bids not exceeding the highest bid, while the seat could
+    /// afford to raise.
+    pub passes: u32,
+    /// The number of cards bought.
+    pub purchases: u32,
+    /// The sum of each raise divided by the highest card value on the stack.
+    value_ratios: f64,
+    /// The sum of each raise divided by the capital of the seat.
+    capital_ratios: f64,
+    /// The sum of each price paid divided by the value of the card bought.
+    price_ratios: f64,
+}
+
+impl BidderProfile {
+    /// The number of observations the prior is worth.
+    const PRIOR_WEIGHT: f64 = 2.0;
+    const PRIOR_VALUE_RATIO: f64 = 0.8;
+    const PRIOR_CAPITAL_RATIO: f64 = 0.4;
+
+    /// Returns the average raise relative to the highest card value on the
+    /// stack.
+    pub fn value_ratio(&self) -> f64 {
+        Self::blend(self.value_ratios, self.raises, Self::PRIOR_VALUE_RATIO)
+    }
+
+    /// Returns the average raise relative to the capital of the seat.
+    pub fn capital_ratio(&self) -> f64 {
+        Self::blend(self.capital_ratios, self.raises, Self::PRIOR_CAPITAL_RATIO)
+    }
+
+    /// Returns the average price paid relative to the value of the card.
+    pub fn price_ratio(&self) -> f64 {
+        Self::blend(self.price_ratios, self.purchases, Self::PRIOR_VALUE_RATIO)
+    }
+
+    /// Returns the fraction of affordable raises the seat passed on.
+    pub fn pass_rate(&self) -> f64 {
+        match self.raises + self.passes {
+            0 => 0.5,
+            bids => self.passes as f64 / bids as f64,
+        }
+    }
+
+    /// Predicts the highest bid the seat would make on a stack whose most
+    /// valuable card is worth `value`, averaging the predictions relative to
+    /// the card value and to the capital.
+    pub fn predict_bid(&self, value: BidValue, capital: BidValue) -> BidValue {
+        let by_value = self.value_ratio() * value as f64;
+        let by_capital = self.capital_ratio() * capital as f64;
+        (((by_value + by_capital) / 2.0).round() as BidValue).clamp(0, capital.max(0))
+    }
+
+    fn blend(sum: f64, count: u32, prior: f64) -> f64 {
+        (sum + prior * Self::PRIOR_WEIGHT) / (count as f64 + Self::PRIOR_WEIGHT)
+    }
+}
+
+/// Keeps a [`BidderProfile`] for every opponent, updated from the history of
+/// the [`GameInfo`]. Profiles are keyed by the identity of the opponent
+/// rather than by seat, such that they follow an opponent across seatings.
+#[derive(Clone, Debug, Default)]
+pub struct OpponentModel {
+    profiles: HashMap<String, BidderProfile>,
+}
+
+impl OpponentModel {
+    /// Returns the profile of an opponent, which only holds the priors if
+    /// the opponent was never observed.
+    pub fn profile(&self, opponent: &str) -> BidderProfile {
+        self.profiles.get(opponent).cloned().unwrap_or_default()
+    }
+
+    /// Updates the profiles of every seat except `me` from the events of the
+    /// history starting at index `from`, where `opponents` holds the identity
+    /// of the player at each seat.
+    pub fn observe(&mut self, info: &GameInfo, from: usize, me: usize, opponents: &[String]) {
+        let history = info.history();
+        // walk backwards to know the stack at each bid, which holds the cards
+        // bought later in the same round and the cards still on the stack
+        let mut later = info.stack().iter().cloned().collect::<Vec<Card>>();
+        for (idx, event) in history.iter().enumerate().rev() {
+            if let GameEvent::Purchase { card, .. } = event {
+                later.push(*card);
+            }
+            if idx >= from && event.player() != me {
+                let value = later.iter().map(|card| card.value()).max().unwrap_or(0);
+                self.record(&opponents[event.player()], event, value);
+            }
+            if let GameEvent::Reinvestment { .. } = event {
+                // the stack was bought out before the reinvestment phase
+                later.clear();
+            }
+        }
+    }
+
+    fn record(&mut self, opponent: &str, event: &GameEvent, stack_value: BidValue) {
+        let profile = self.profiles.entry(opponent.to_string()).or_default();
+        match *event {
+            GameEvent::Bid {
+                bid,
+                highest,
+                capital,
+                ..
+            } => {
+                if bid > highest {
+                    profile.raises += 1;
+                    profile.value_ratios += bid as f64 / stack_value.max(1) as f64;
+                    profile.capital_ratios += bid as f64 / capital.max(1) as f64;
+                } else if capital > highest {
+                    profile.passes += 1;
+                }
+            }
+            GameEvent::Purchase { card, price, .. } => {
+                profile.purchases += 1;
+                profile.price_ratios += price.max(0) as f64 / card.value() as f64;
+            }
+            GameEvent::Reinvestment { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use gemstone::*;
+
+use super::{Evaluator, NeuralEvaluator};
+
+/// A behavior which tries every legal [`Action`] of the environment action
+/// space and plays the one leading to the position its [`Evaluator`] rates
+/// highest.
+#[derive(Clone, Debug, Default)]
+pub struct NeuralBehavior<E: Evaluator = NeuralEvaluator> {
+    evaluator: E,
+}
+
+impl<E: Evaluator> NeuralBehavior<E> {
+    pub fn new(evaluator: E) -> Self {
+        Self { evaluator }
+    }
+
+    #[inline]
+    pub fn evaluator(&self) -> &E {
+        &self.evaluator
+    }
+
+    /// Returns the best legal move for the player making the next decision.
+    pub fn choose(&self, info: &GameInfo) -> Move {
+        best_action(&self.evaluator, info)
+            .map(|(_, next)| next)
+            .expect("asked for a decision without any legal moves")
+    }
+}
+
+/// Returns the legal action leading to the position rated highest by the
+/// evaluator for the player making the next decision.
+pub(crate) fn best_action(evaluator: &impl Evaluator, info: &GameInfo) -> Option<(Action, Move)> {
+    let (player, _) = info.next_decision()?;
+    Action::legal_moves(info)
+        .into_iter()
+        .filter_map(|(action, next)| {
+            let mut after = info.clone();
+            next.apply(&mut after).ok()?;
+            Some((action, next, evaluator.evaluate(&after, player)))
+        })
+        .max_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+        .map(|(action, next, _)| (action, next))
+}
+
+impl<E: Evaluator> PlayerBehavior for NeuralBehavior<E> {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        match self.choose(info) {
+            Move::Bid(bid) => bid,
+            _ => 0,
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        match self.choose(info) {
+            Move::Pick(slot, payment) => (slot, payment),
+            _ => (0, CardChoice::NONE),
+        }
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        match self.choose(info) {
+            Move::Flip(choice) => choice,
+            _ => CardChoice::NONE,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::{io, path::Path};
+
+use gemstone::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::Mlp;
+
+/// Estimates how good a position is for a player.
+pub trait Evaluator {
+    /// Returns the expected final score of `player` minus the average final
+    /// score of the other players, where higher is better.
+    fn evaluate(&self, info: &GameInfo, player: usize) -> f32;
+}
+
+/// An [`Evaluator`] using an [`Mlp`] on the [`FeatureEncoder`] features,
+/// with a single output predicting the final score margin divided by
+/// [`SCALE`](Self::SCALE).
+#[derive(Clone, Debug)]
+pub struct NeuralEvaluator {
+    network: Mlp,
+}
+
+impl Default for NeuralEvaluator {
+    /// Creates an untrained evaluator with a fixed initialisation.
+    fn default() -> Self {
+        Self::new(Mlp::new(
+            &Self::DEFAULT_SIZES,
+            &mut StdRng::seed_from_u64(0),
+        ))
+    }
+}
+
+impl NeuralEvaluator {
+    /// The factor between the network output and the score margin.
+    pub const SCALE: f32 = 10.0;
+    /// The layer sizes used by [`default`](Self::default).
+    pub const DEFAULT_SIZES: [usize; 4] = [FeatureEncoder::LEN, 64, 32, 1];
+
+    pub fn new(network: Mlp) -> Self {
+        assert_eq!(network.sizes()[0], FeatureEncoder::LEN);
+        Self { network }
+    }
+
+    /// Creates an evaluator from a network saved to a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let network = Mlp::load(path)?;
+        if network.sizes()[0] != FeatureEncoder::LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "network expects {} features, but the encoding has {}",
+                    network.sizes()[0],
+                    FeatureEncoder::LEN
+                ),
+            ));
+        }
+        Ok(Self { network })
+    }
+
+    #[inline]
+    pub fn network(&self) -> &Mlp {
+        &self.network
+    }
+
+    #[inline]
+    pub fn network_mut(&mut self) -> &mut Mlp {
+        &mut self.network
+    }
+}
+
+impl Evaluator for NeuralEvaluator {
+    fn evaluate(&self, info: &GameInfo, player: usize) -> f32 {
+        self.network.forward(&FeatureEncoder::encode(info, player))[0] * Self::SCALE
+    }
+}
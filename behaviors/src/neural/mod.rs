@@ -0,0 +1,9 @@
+mod behavior;
+mod evaluator;
+mod network;
+mod trainer;
+
+pub use behavior::NeuralBehavior;
+pub use evaluator::{Evaluator, NeuralEvaluator};
+pub use network::Mlp;
+pub use trainer::{Sample, SelfPlayTrainer};
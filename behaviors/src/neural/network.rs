@@ -0,0 +1,262 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+use rand::Rng;
+
+const HEADER: &str = "# gem mlp v1";
+
+/// A fully connected layer, where the weights are stored row-major with one
+/// row per output.
+#[derive(Clone, Debug)]
+struct Layer {
+    inputs: usize,
+    outputs: usize,
+    weights: Vec<f32>,
+    biases: Vec<f32>,
+}
+
+impl Layer {
+    fn forward(&self, input: &[f32], output: &mut Vec<f32>) {
+        output.clear();
+        output.extend(
+            self.weights
+                .chunks(self.inputs)
+                .zip(&self.biases)
+                .map(|(row, bias)| row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>() + bias),
+        );
+    }
+}
+
+/// A small multilayer perceptron with ReLU hidden layers and a linear output
+/// layer, trained with stochastic gradient descent on the squared error.
+#[derive(Clone, Debug)]
+pub struct Mlp {
+    layers: Vec<Layer>,
+}
+
+impl Mlp {
+    /// Creates a network with the given layer sizes, starting with the input
+    /// size and ending with the output size. Weights are initialised using
+    /// He-uniform initialisation.
+    pub fn new(sizes: &[usize], rng: &mut impl Rng) -> Self {
+        assert!(sizes.len() >= 2);
+        let layers = sizes
+            .windows(2)
+            .map(|pair| {
+                let (inputs, outputs) = (pair[0], pair[1]);
+                let limit = (6.0 / inputs as f32).sqrt();
+                Layer {
+                    inputs,
+                    outputs,
+                    weights: (0..inputs * outputs)
+                        .map(|_| rng.gen_range(-limit..limit))
+                        .collect(),
+                    biases: vec![0.0; outputs],
+                }
+            })
+            .collect();
+        Self { layers }
+    }
+
+    /// Returns the layer sizes, starting with the input size.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![self.layers[0].inputs];
+        sizes.extend(self.layers.iter().map(|layer| layer.outputs));
+        sizes
+    }
+
+    pub fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.activations(input).pop().unwrap()
+    }
+
+    /// Returns the output of every layer, starting with the input itself.
+    fn activations(&self, input: &[f32]) -> Vec<Vec<f32>> {
+        let mut activations = vec![input.to_vec()];
+        for (i, layer) in self.layers.iter().enumerate() {
+            let mut output = Vec::with_capacity(layer.outputs);
+            layer.forward(activations.last().unwrap(), &mut output);
+            if i + 1 < self.layers.len() {
+                output.iter_mut().for_each(|x| *x = x.max(0.0));
+            }
+            activations.push(output);
+        }
+        activations
+    }
+
+    /// Takes a single gradient descent step towards `target`, returning the
+    /// mean squared error before the step.
+    pub fn train(&mut self, input: &[f32], target: &[f32], learning_rate: f32) -> f32 {
+        let activations = self.activations(input);
+        let output = activations.last().unwrap();
+        let mut delta = output
+            .iter()
+            .zip(target)
+            .map(|(y, t)| y - t)
+            .collect::<Vec<_>>();
+        let loss = delta.iter().map(|d| d * d).sum::<f32>() / delta.len() as f32;
+
+        for (i, layer) in self.layers.iter_mut().enumerate().rev() {
+            let input = &activations[i];
+            // propagate the error before updating the weights of this layer
+            let previous = match i {
+                0 => Vec::new(),
+                _ => (0..layer.inputs)
+                    .map(|j| match input[j] > 0.0 {
+                        true => (0..layer.outputs)
+                            .map(|k| layer.weights[k * layer.inputs + j] * delta[k])
+                            .sum(),
+                        false => 0.0,
+                    })
+                    .collect(),
+            };
+            for (k, d) in delta.iter().enumerate() {
+                let row = &mut layer.weights[k * layer.inputs..(k + 1) * layer.inputs];
+                row.iter_mut()
+                    .zip(input)
+                    .for_each(|(w, x)| *w -= learning_rate * d * x);
+                layer.biases[k] -= learning_rate * d;
+            }
+            delta = previous;
+        }
+        loss
+    }
+
+    /// Saves the network as text, with the layer sizes on the first line
+    /// followed by the weights and biases of each layer on a line each.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let join = |values: &[f32]| {
+            values
+                .iter()
+                .map(f32::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let sizes = self
+            .sizes()
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>();
+
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "{HEADER}")?;
+        writeln!(file, "{}", sizes.join(" "))?;
+        for layer in &self.layers {
+            writeln!(file, "{}", join(&layer.weights))?;
+            writeln!(file, "{}", join(&layer.biases))?;
+        }
+        file.flush()
+    }
+
+    /// Loads a network previously written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let invalid = |what: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid network file: {what}"),
+            )
+        };
+        let mut lines = BufReader::new(fs::File::open(path)?)
+            .lines()
+            .filter(|line| {
+                line.as_ref().map_or(true, |line| {
+                    !line.starts_with('#') && !line.trim().is_empty()
+                })
+            });
+        let mut next_values = |what: &str, len: usize| -> io::Result<Vec<f32>> {
+            let line = lines.next().ok_or_else(|| invalid(what))??;
+            let values = line
+                .split_whitespace()
+                .map(|value| value.parse().map_err(|_| invalid(what)))
+                .collect::<io::Result<Vec<f32>>>()?;
+            match len == 0 || values.len() == len {
+                true => Ok(values),
+                false => Err(invalid(what)),
+            }
+        };
+
+        let sizes = next_values("layer sizes", 0)?
+            .into_iter()
+            .map(|size| size as usize)
+            .collect::<Vec<_>>();
+        if sizes.len() < 2 || sizes.contains(&0) {
+            return Err(invalid("layer sizes"));
+        }
+        let layers = sizes
+            .windows(2)
+            .map(|pair| {
+                Ok(Layer {
+                    inputs: pair[0],
+                    outputs: pair[1],
+                    weights: next_values("weights", pair[0] * pair[1])?,
+                    biases: next_values("biases", pair[1])?,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { layers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    /// Returns half the summed squared error, whose gradient is followed by
+    /// [`Mlp::train`].
+    fn loss(mlp: &Mlp, input: &[f32], target: &[f32]) -> f64 {
+        mlp.forward(input)
+            .iter()
+            .zip(target)
+            .map(|(y, t)| (*y as f64 - *t as f64).powi(2) / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn training_follows_the_numeric_gradient() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mlp = Mlp::new(&[5, 8, 6, 2], &mut rng);
+        let input = (0..5)
+            .map(|_| rng.gen_range(-1.0..1.0))
+            .collect::<Vec<f32>>();
+        let target = [0.5, -1.5];
+
+        // a step with a learning rate of one moves every parameter by its
+        // gradient
+        let mut trained = mlp.clone();
+        trained.train(&input, &target, 1.0);
+        assert_ne!(trained.forward(&input), mlp.forward(&input));
+
+        let eps = 1e-3;
+        for (layer, (before, after)) in mlp.layers.iter().zip(&trained.layers).enumerate() {
+            let parameters = before.weights.len() + before.biases.len();
+            for idx in 0..parameters {
+                let mut shifted = [mlp.clone(), mlp.clone()];
+                for (mlp, sign) in shifted.iter_mut().zip([1.0, -1.0]) {
+                    let layer = &mut mlp.layers[layer];
+                    match idx < layer.weights.len() {
+                        true => layer.weights[idx] += sign * eps,
+                        false => layer.biases[idx - layer.weights.len()] += sign * eps,
+                    }
+                }
+                let numeric = (loss(&shifted[0], &input, &target)
+                    - loss(&shifted[1], &input, &target))
+                    / (2.0 * eps as f64);
+                let analytic = match idx < before.weights.len() {
+                    true => before.weights[idx] - after.weights[idx],
+                    false => {
+                        let idx = idx - before.weights.len();
+                        before.biases[idx] - after.biases[idx]
+                    }
+                } as f64;
+                assert!(
+                    (numeric - analytic).abs() <= 1e-2 * (1.0 + numeric.abs()),
+                    "layer {layer} parameter {idx}: {numeric} vs {analytic}"
+                );
+            }
+        }
+    }
+}
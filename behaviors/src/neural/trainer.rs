@@ -0,0 +1,113 @@
+use gemstone::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::{behavior::best_action, NeuralEvaluator};
+
+/// A single training position, holding the [`FeatureEncoder`] features from
+/// the perspective of one seat and the final score margin of that seat
+/// divided by [`NeuralEvaluator::SCALE`].
+pub type Sample = (Vec<f32>, f32);
+
+/// Trains a [`NeuralEvaluator`] by self-play, where every seat is played by
+/// the evaluator itself and each position is labelled with the final scores
+/// of the game.
+pub struct SelfPlayTrainer {
+    evaluator: NeuralEvaluator,
+    num_players: usize,
+    learning_rate: f32,
+    /// The probability of playing a random legal move instead of the best.
+    exploration: f64,
+    rng: StdRng,
+}
+
+impl SelfPlayTrainer {
+    pub fn new(evaluator: NeuralEvaluator, num_players: usize) -> Self {
+        assert!((2..=4).contains(&num_players));
+        Self {
+            evaluator,
+            num_players,
+            learning_rate: 0.001,
+            exploration: 0.1,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Seeds the random number generator used for exploration, shuffling and
+    /// dealing, such that training is reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    #[inline]
+    pub fn evaluator(&self) -> &NeuralEvaluator {
+        &self.evaluator
+    }
+
+    pub fn into_evaluator(self) -> NeuralEvaluator {
+        self.evaluator
+    }
+
+    /// Plays a single self-play game dealt from `seed`, returning every
+    /// position from the perspective of every seat.
+    pub fn play(&mut self, seed: u64) -> Result<Vec<Sample>> {
+        let mut env = GemEnv::default();
+        (0..self.num_players).for_each(|_| env.add_agent());
+        env.reset(seed)?;
+
+        let mut positions = Vec::new();
+        let rewards = loop {
+            let info = env.info().unwrap();
+            for seat in 0..self.num_players {
+                positions.push((FeatureEncoder::encode(info, seat), seat));
+            }
+
+            let action = match self.rng.gen_bool(self.exploration) {
+                true => Action::legal_moves(info)
+                    .choose(&mut self.rng)
+                    .map(|&(action, _)| action),
+                false => best_action(&self.evaluator, info).map(|(action, _)| action),
+            };
+            let step = env.step(action.ok_or(GemError::IllegalAction)?.index())?;
+            if step.done {
+                break step.rewards;
+            }
+        };
+
+        Ok(positions
+            .into_iter()
+            .map(|(features, seat)| (features, rewards[seat] / NeuralEvaluator::SCALE))
+            .collect())
+    }
+
+    /// Plays the given number of self-play games, training on the positions
+    /// of each game after it ends. Returns the mean squared error over all
+    /// positions, measured before each update.
+    pub fn train(&mut self, games: usize) -> Result<f32> {
+        let mut loss = 0.0;
+        let mut samples = 0;
+        for _ in 0..games {
+            let seed = self.rng.gen();
+            let mut positions = self.play(seed)?;
+            positions.shuffle(&mut self.rng);
+            for (features, target) in &positions {
+                loss +=
+                    self.evaluator
+                        .network_mut()
+                        .train(features, &[*target], self.learning_rate);
+            }
+            samples += positions.len();
+        }
+        Ok(loss / samples.max(1) as f32)
+    }
+}
@@ -0,0 +1,117 @@
+use std::io;
+
+use gemstone::*;
+
+use crate::{
+    CfrBehavior, GreedyBehavior, HeuristicBehavior, HeuristicParams, ModellingBehavior,
+    NeuralBehavior, NeuralEvaluator, RandomBehavior,
+};
+
+/// Registers every bot of this crate, where parameters are written as
+/// `name:key=value,...` and every parameter is optional unless noted:
+///
+/// - `random:seed=<u64>`, where the seed makes the choices repeatable
+/// - `greedy:gem=<f32>,sole_majority=<f32>,shared_majority=<f32>,denial=<f32>,exchange_rate=<f32>`
+/// - `heuristic:gem_value=<f32>,majority_bonus=<f32>,capital_reserve=<f32>,bid_rate=<f32>`
+/// - `modelling`, with the same parameters as `heuristic`
+/// - `cfr:table=<path>`, where the required path is a saved strategy table
+/// - `neural:network=<path>`, where the required path is a saved network
+pub fn register(registry: &mut BehaviorRegistry) {
+    registry.register("random", "makes a random legal move", |params| {
+        Ok(Box::new(match params.get_opt("seed")? {
+            Some(seed) => RandomBehavior::with_seed(seed),
+            None => RandomBehavior::default(),
+        }))
+    });
+    registry.register(
+        "greedy",
+        "bids the full value of the most valuable card",
+        |params| {
+            let defaults = ValuationWeights::default();
+            let weights = ValuationWeights {
+                gem: params.get("gem", defaults.gem)?,
+                sole_majority: params.get("sole_majority", defaults.sole_majority)?,
+                shared_majority: params.get("shared_majority", defaults.shared_majority)?,
+                denial: params.get("denial", defaults.denial)?,
+                exchange_rate: params.get("exchange_rate", defaults.exchange_rate)?,
+            };
+            Ok(Box::new(GreedyBehavior::new(weights)))
+        },
+    );
+    registry.register(
+        "heuristic",
+        "values cards and capital with tunable weights",
+        |params| Ok(Box::new(HeuristicBehavior::new(heuristic_params(params)?))),
+    );
+    registry.register(
+        "modelling",
+        "shades its bids by modelling the opponents",
+        |params| {
+            let behavior = ModellingBehavior::default().with_params(heuristic_params(params)?);
+            Ok(Box::new(behavior))
+        },
+    );
+    registry.register(
+        "cfr",
+        "bids by a strategy table trained with counterfactual regret minimization",
+        |params| {
+            let path = required(params, "table", "cfr")?;
+            Ok(Box::new(
+                CfrBehavior::load(&path).map_err(|err| load_error(&path, err))?,
+            ))
+        },
+    );
+    registry.register(
+        "neural",
+        "picks the action with the best position evaluated by a network",
+        |params| {
+            let path = required(params, "network", "neural")?;
+            let evaluator = NeuralEvaluator::load(&path).map_err(|err| load_error(&path, err))?;
+            Ok(Box::new(NeuralBehavior::new(evaluator)))
+        },
+    );
+}
+
+fn heuristic_params(params: &mut BehaviorParams) -> Result<HeuristicParams> {
+    let defaults = HeuristicParams::default();
+    Ok(HeuristicParams {
+        gem_value: params.get("gem_value", defaults.gem_value)?,
+        majority_bonus: params.get("majority_bonus", defaults.majority_bonus)?,
+        capital_reserve: params.get("capital_reserve", defaults.capital_reserve)?,
+        bid_rate: params.get("bid_rate", defaults.bid_rate)?,
+    })
+}
+
+/// Returns a parameter which has no meaningful default, such as the path of
+/// a trained model.
+fn required(params: &mut BehaviorParams, key: &str, name: &str) -> Result<String> {
+    params
+        .get_opt(key)?
+        .ok_or_else(|| GemError::InvalidBehaviorSpec(format!("missing `{key}` for `{name}`")))
+}
+
+fn load_error(path: &str, err: io::Error) -> GemError {
+    GemError::InvalidBehaviorSpec(format!("cannot load `{path}`: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trained_models_need_a_path() {
+        let mut registry = BehaviorRegistry::default();
+        register(&mut registry);
+
+        assert!(registry.create("random:seed=3").is_ok());
+        assert!(registry
+            .create("heuristic:bid_rate=0.7,gem_value=2")
+            .is_ok());
+        for spec in ["cfr", "neural", "cfr:table=missing.txt"] {
+            assert!(
+                matches!(registry.create(spec), Err(GemError::InvalidBehaviorSpec(_))),
+                "{spec}"
+            );
+        }
+    }
+}
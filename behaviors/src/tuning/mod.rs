@@ -0,0 +1,5 @@
+mod spsa;
+mod tunable;
+
+pub use spsa::{SpsaStep, SpsaTuner};
+pub use tunable::Tunable;
@@ -0,0 +1,414 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use gemstone::GameSetup;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::Tunable;
+
+const HEADER: &str = "# gem spsa checkpoint v1";
+
+/// The outcome of a single [`SpsaTuner::step`].
+#[derive(Clone, Debug)]
+pub struct SpsaStep {
+    /// The index of the iteration, starting at zero.
+    pub iteration: usize,
+    /// The average score margin of the positive over the negative
+    /// perturbation.
+    pub difference: f64,
+    /// The parameters after the update.
+    pub params: Vec<f64>,
+    /// The average score margin of the parameters over the baseline, if
+    /// they were validated in this iteration.
+    pub validation: Option<f64>,
+}
+
+/// Tunes the parameters of a bot using simultaneous perturbation stochastic
+/// approximation (SPSA).
+///
+/// Every iteration perturbs all parameters at once in a random direction,
+/// plays the positive against the negative perturbation in pairs of seeded
+/// games with swapped seats, and moves the parameters along the direction in
+/// proportion to the score margin. Parameters are stepped in units of their
+/// [`scales`](Tunable::scales).
+///
+/// The parameters are regularly validated against a baseline on a fixed set
+/// of seeds, and the best validated parameters are kept. When a checkpoint is
+/// set, the state is saved after every validation and loaded again by
+/// [`resume`](Self::resume).
+pub struct SpsaTuner<P: Tunable> {
+    baseline: P,
+    /// The current parameters, divided by their scales.
+    theta: Vec<f64>,
+    iteration: usize,
+    best: Option<(Vec<f64>, f64)>,
+
+    iterations: usize,
+    pairs_per_iteration: usize,
+    validation_pairs: usize,
+    validation_interval: usize,
+    players_per_game: usize,
+    learning_rate: f64,
+    perturbation: f64,
+    seed: u64,
+    checkpoint: Option<PathBuf>,
+}
+
+impl<P: Tunable> SpsaTuner<P> {
+    /// Creates a tuner starting from `initial`, which also serves as the
+    /// baseline for validation.
+    pub fn new(initial: P) -> Self {
+        let theta = initial
+            .to_vector()
+            .iter()
+            .zip(initial.scales())
+            .map(|(value, scale)| value / scale)
+            .collect();
+        Self {
+            baseline: initial,
+            theta,
+            iteration: 0,
+            best: None,
+            iterations: 200,
+            pairs_per_iteration: 8,
+            validation_pairs: 100,
+            validation_interval: 10,
+            players_per_game: 2,
+            learning_rate: 0.5,
+            perturbation: 1.0,
+            seed: 0,
+            checkpoint: None,
+        }
+    }
+
+    pub fn with_baseline(mut self, baseline: P) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// Sets the total number of iterations played by [`run`](Self::run).
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the number of game pairs played to estimate each gradient.
+    pub fn with_pairs_per_iteration(mut self, pairs: usize) -> Self {
+        self.pairs_per_iteration = pairs.max(1);
+        self
+    }
+
+    /// Sets the number of game pairs played against the baseline, and the
+    /// number of iterations between validations.
+    pub fn with_validation(mut self, pairs: usize, interval: usize) -> Self {
+        self.validation_pairs = pairs.max(1);
+        self.validation_interval = interval.max(1);
+        self
+    }
+
+    /// Sets the number of players in each game, in range `[2..5)`. Seats
+    /// besides the two compared parameter sets are taken by the baseline.
+    pub fn with_players_per_game(mut self, players: usize) -> Self {
+        assert!((2..=4).contains(&players));
+        self.players_per_game = players;
+        self
+    }
+
+    /// Sets the step size of the first iteration, in units of the scales.
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Sets the size of the first perturbation, in units of the scales.
+    pub fn with_perturbation(mut self, perturbation: f64) -> Self {
+        self.perturbation = perturbation;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the file the state of the tuner is saved to.
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    #[inline]
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Returns the current parameters.
+    pub fn params(&self) -> P {
+        self.decode(&self.theta)
+    }
+
+    /// Returns the best validated parameters together with their average
+    /// score margin over the baseline.
+    pub fn best(&self) -> Option<(P, f64)> {
+        self.best
+            .as_ref()
+            .map(|(theta, score)| (self.decode(theta), *score))
+    }
+
+    /// Plays every remaining iteration and returns the best validated
+    /// parameters. This function will return an error if the checkpoint
+    /// cannot be written.
+    pub fn run(&mut self) -> io::Result<P> {
+        while self.iteration < self.iterations {
+            self.step()?;
+        }
+        if self.best.is_none() {
+            self.validate()?;
+        }
+        Ok(self.best().unwrap().0)
+    }
+
+    /// Plays a single iteration, validating and saving a checkpoint if the
+    /// iteration is at the end of an interval.
+    pub fn step(&mut self) -> io::Result<SpsaStep> {
+        let k = self.iteration as f64;
+        let stability = self.iterations as f64 / 10.0;
+        let step_size = self.learning_rate / (k + 1.0 + stability).powf(0.602);
+        let perturbation = self.perturbation / (k + 1.0).powf(0.101);
+
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(self.iteration as u64));
+        let direction = self
+            .theta
+            .iter()
+            .map(|_| match rng.gen_bool(0.5) {
+                true => 1.0,
+                false => -1.0,
+            })
+            .collect::<Vec<f64>>();
+        let shifted = |sign: f64| {
+            self.theta
+                .iter()
+                .zip(&direction)
+                .map(|(theta, delta)| theta + sign * perturbation * delta)
+                .collect::<Vec<_>>()
+        };
+        let plus = self.decode(&shifted(1.0));
+        let minus = self.decode(&shifted(-1.0));
+
+        let seed = self
+            .seed
+            .wrapping_add((self.iteration * self.pairs_per_iteration) as u64);
+        let difference = self.margin(&plus, &minus, self.pairs_per_iteration, seed);
+        for (theta, delta) in self.theta.iter_mut().zip(&direction) {
+            *theta += step_size * difference / (2.0 * perturbation * delta);
+        }
+        // keep the parameters within their valid ranges
+        self.theta = self.encode(&self.params());
+        self.iteration += 1;
+
+        let validation = match self.iteration.is_multiple_of(self.validation_interval) {
+            true => Some(self.validate()?),
+            false => None,
+        };
+        Ok(SpsaStep {
+            iteration: self.iteration - 1,
+            difference,
+            params: self.params().to_vector(),
+            validation,
+        })
+    }
+
+    /// Plays the current parameters against the baseline, keeps them if they
+    /// are the best so far and saves a checkpoint.
+    fn validate(&mut self) -> io::Result<f64> {
+        let params = self.params();
+        // the same seeds are used every time, such that scores are comparable
+        let seed = self.seed.wrapping_add(1 << 32);
+        let score = self.margin(&params, &self.baseline, self.validation_pairs, seed);
+        if self.best.as_ref().is_none_or(|(_, best)| score > *best) {
+            self.best = Some((self.theta.clone(), score));
+        }
+        if let Some(path) = &self.checkpoint {
+            self.save(path)?;
+        }
+        Ok(score)
+    }
+
+    /// Returns the average score margin of `a` over `b`, playing pairs of
+    /// games where both sides swap seats on the same deal. Games ending with
+    /// an error are skipped.
+    fn margin(&self, a: &P, b: &P, pairs: usize, seed: u64) -> f64 {
+        let mut total = 0.0;
+        let mut games = 0;
+        for pair in 0..pairs {
+            for (seat_a, seat_b) in [(0, 1), (1, 0)] {
+                let mut setup = GameSetup::default();
+                for seat in 0..self.players_per_game {
+                    let params = match seat {
+                        _ if seat == seat_a => a,
+                        _ if seat == seat_b => b,
+                        _ => &self.baseline,
+                    };
+                    setup.insert_player(params.behavior()).unwrap();
+                }
+                setup.set_seed(seed.wrapping_add(pair as u64));
+
+                if let Ok(scores) = setup.finish().and_then(|mut game| game.run()) {
+                    total += (scores.get(seat_a) - scores.get(seat_b)) as f64;
+                    games += 1;
+                }
+            }
+        }
+        total / games.max(1) as f64
+    }
+
+    fn decode(&self, theta: &[f64]) -> P {
+        let params = theta
+            .iter()
+            .zip(self.baseline.scales())
+            .map(|(theta, scale)| theta * scale)
+            .collect::<Vec<_>>();
+        self.baseline.with_vector(&params)
+    }
+
+    fn encode(&self, params: &P) -> Vec<f64> {
+        params
+            .to_vector()
+            .iter()
+            .zip(self.baseline.scales())
+            .map(|(value, scale)| value / scale)
+            .collect()
+    }
+}
+
+//
+// Persistence
+//
+
+impl<P: Tunable> SpsaTuner<P> {
+    /// Saves the iteration, the current parameters and the best validated
+    /// parameters as text.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let join = |params: &P| {
+            params
+                .to_vector()
+                .iter()
+                .map(f64::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "{HEADER}")?;
+        writeln!(file, "# {}", self.baseline.names().join(" "))?;
+        writeln!(file, "iteration {}", self.iteration)?;
+        writeln!(file, "params {}", join(&self.params()))?;
+        if let Some((best, score)) = self.best() {
+            writeln!(file, "best {}", join(&best))?;
+            writeln!(file, "score {score}")?;
+        }
+        file.flush()
+    }
+
+    /// Loads the state saved in the checkpoint, if the checkpoint is set and
+    /// the file exists. Returns whether the state was loaded.
+    pub fn resume(&mut self) -> io::Result<bool> {
+        let path = match &self.checkpoint {
+            Some(path) => path,
+            None => return Ok(false),
+        };
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid checkpoint line: {line}"),
+            )
+        };
+        let len = self.theta.len();
+        let parse = |line: &str, values: &str| {
+            let values = values
+                .split_whitespace()
+                .map(|value| value.parse().map_err(|_| invalid(line)))
+                .collect::<io::Result<Vec<f64>>>()?;
+            match values.len() == len {
+                true => Ok(self.encode(&self.baseline.with_vector(&values))),
+                false => Err(invalid(line)),
+            }
+        };
+
+        let (mut iteration, mut theta, mut best, mut score) = (None, None, None, None);
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            match line.split_once(' ') {
+                Some(("iteration", value)) => {
+                    iteration = Some(value.trim().parse().map_err(|_| invalid(&line))?)
+                }
+                Some(("params", values)) => theta = Some(parse(&line, values)?),
+                Some(("best", values)) => best = Some(parse(&line, values)?),
+                Some(("score", value)) => {
+                    score = Some(value.trim().parse().map_err(|_| invalid(&line))?)
+                }
+                _ => return Err(invalid(&line)),
+            }
+        }
+
+        self.iteration = iteration.ok_or_else(|| invalid("missing iteration"))?;
+        self.theta = theta.ok_or_else(|| invalid("missing params"))?;
+        self.best = best.zip(score);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HeuristicParams;
+
+    fn tuner(checkpoint: &Path) -> SpsaTuner<HeuristicParams> {
+        SpsaTuner::new(HeuristicParams::default())
+            .with_iterations(4)
+            .with_pairs_per_iteration(1)
+            .with_validation(1, 2)
+            .with_seed(17)
+            .with_checkpoint(checkpoint)
+    }
+
+    #[test]
+    fn resumed_tuners_continue_where_they_stopped() {
+        let path = std::env::temp_dir().join(format!("gem-spsa-{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut uninterrupted = tuner(&path);
+        assert!(!uninterrupted.resume().unwrap());
+        let expected = uninterrupted.run().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // stop after the first validation, which saved the checkpoint
+        let mut interrupted = tuner(&path);
+        interrupted.step().unwrap();
+        assert!(interrupted.step().unwrap().validation.is_some());
+
+        let mut resumed = tuner(&path);
+        let loaded = resumed.resume();
+        let state = (resumed.iteration(), resumed.params());
+        let result = resumed.run();
+        fs::remove_file(&path).unwrap();
+
+        assert!(loaded.unwrap());
+        assert_eq!(state, (2, interrupted.params()));
+        assert_eq!(resumed.iteration(), 4);
+        assert_eq!(result.unwrap(), expected);
+        assert_eq!(resumed.params(), uninterrupted.params());
+        assert_eq!(resumed.best().unwrap().1, uninterrupted.best().unwrap().1);
+    }
+}
@@ -0,0 +1,52 @@
+use gemstone::PlayerBehavior;
+
+use crate::{HeuristicBehavior, HeuristicParams};
+
+/// A set of bot parameters which can be treated as a vector of numbers.
+pub trait Tunable: Clone {
+    /// Returns the name of each parameter, in vector order.
+    fn names(&self) -> Vec<&'static str>;
+    fn to_vector(&self) -> Vec<f64>;
+    /// Returns a copy with the parameters taken from the vector, clamped to
+    /// their valid ranges.
+    fn with_vector(&self, params: &[f64]) -> Self;
+    /// Returns the typical magnitude of a meaningful change of each parameter,
+    /// used to scale the steps of the tuner.
+    fn scales(&self) -> Vec<f64>;
+    /// Creates a behavior playing with these parameters.
+    fn behavior(&self) -> Box<dyn PlayerBehavior>;
+}
+
+impl Tunable for HeuristicParams {
+    fn names(&self) -> Vec<&'static str> {
+        vec!["gem_value", "majority_bonus", "capital_reserve", "bid_rate"]
+    }
+
+    fn to_vector(&self) -> Vec<f64> {
+        [
+            self.gem_value,
+            self.majority_bonus,
+            self.capital_reserve,
+            self.bid_rate,
+        ]
+        .map(f64::from)
+        .to_vec()
+    }
+
+    fn with_vector(&self, params: &[f64]) -> Self {
+        Self {
+            gem_value: params[0].clamp(0.0, 10.0) as f32,
+            majority_bonus: params[1].clamp(0.0, 20.0) as f32,
+            capital_reserve: params[2].clamp(0.0, 5.0) as f32,
+            bid_rate: params[3].clamp(0.0, 3.0) as f32,
+        }
+    }
+
+    fn scales(&self) -> Vec<f64> {
+        vec![0.2, 0.5, 0.1, 0.1]
+    }
+
+    fn behavior(&self) -> Box<dyn PlayerBehavior> {
+        Box::new(HeuristicBehavior::new(*self))
+    }
+}
@@ -0,0 +1,9 @@
+mod payment;
+mod reinvestment;
+mod valuation;
+
+pub use payment::{
+    KeepCoins, MinimalOverpay, Payment, PaymentCost, PaymentOptimizer, ProtectMajorities,
+};
+pub use reinvestment::{FlipPlan, ReinvestmentPlanner, ReinvestmentWeights};
+pub use valuation::{BidValuation, CardValue, ValuationWeights};
@@ -0,0 +1,216 @@
+use crate::{BidValue, Card, CardChoice, CardIterator, GameInfo, GemType, PlayerInventory};
+
+/// A single way of covering a bid, ranked by a [`PaymentCost`]-model.
+#[derive(Clone, Copy, Debug)]
+pub struct Payment {
+    /// The chosen cards, relative to the inventory the optimizer was created from.
+    pub choice: CardChoice,
+    /// The total value of the chosen cards.
+    pub value: BidValue,
+    /// The cost assigned by the cost model, where lower is better.
+    pub cost: f32,
+}
+
+/// A cost model used by the [`PaymentOptimizer`] to rank payments.
+pub trait PaymentCost {
+    /// Returns the cost of paying `price` with `cards`. All cards are
+    /// non-leveraged and their total value covers the price.
+    fn cost(&self, cards: &[Card], price: BidValue) -> f32;
+}
+
+/// Prefers the payment which overpays the least.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinimalOverpay;
+
+impl PaymentCost for MinimalOverpay {
+    fn cost(&self, cards: &[Card], price: BidValue) -> f32 {
+        (cards.iter().capital() - price) as f32
+    }
+}
+
+/// Prefers paying with gem cards, such that coin cards are kept available for
+/// the remaining auctions of the round.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeepCoins;
+
+impl PaymentCost for KeepCoins {
+    fn cost(&self, cards: &[Card], price: BidValue) -> f32 {
+        let coins = cards.iter().coin_cards().capital();
+        let overpay = cards.iter().capital() - price;
+        coins as f32 + overpay as f32 * 0.1
+    }
+}
+
+/// Prefers giving up the gems which are worth the least, where each gem type
+/// is weighted by how much the player stands to lose by leveraging it.
+#[derive(Clone, Copy, Debug)]
+pub struct ProtectMajorities {
+    /// The cost of leveraging a single gem of each [`GemType`], indexed by
+    /// the gem type.
+    pub weights: [f32; 6],
+}
+
+impl Default for ProtectMajorities {
+    fn default() -> Self {
+        Self { weights: [1.0; 6] }
+    }
+}
+
+impl ProtectMajorities {
+    /// Creates weights for the given player, such that gems of any type where
+    /// the player currently holds or shares the majority are protected.
+    pub fn from_info(info: &GameInfo, player: usize) -> Self {
+        let mut weights = [1.0; 6];
+        for gem in GemType::iter() {
+            if info.majorities().majority(gem).holds(player) {
+                weights[gem as usize] += 2.0;
+            }
+        }
+        Self { weights }
+    }
+}
+
+impl PaymentCost for ProtectMajorities {
+    fn cost(&self, cards: &[Card], price: BidValue) -> f32 {
+        let gems = cards
+            .iter()
+            .gem_cards()
+            .flat_map(|card| card.archtype().gems())
+            .map(|gem| self.weights[gem as usize])
+            .sum::<f32>();
+        let coins = cards.iter().coin_cards().capital();
+        let overpay = cards.iter().capital() - price;
+        gems + coins as f32 * 0.05 + overpay as f32 * 0.1
+    }
+}
+
+/// Finds the ways of paying for a bid using the non-leveraged cards of a
+/// [`PlayerInventory`]. Only minimal payments are considered, meaning that
+/// removing any single card from a payment would no longer cover the price.
+pub struct PaymentOptimizer {
+    /// The non-leveraged cards of the inventory, with their inventory index.
+    cards: Vec<(usize, Card)>,
+}
+
+impl PaymentOptimizer {
+    pub fn new(inventory: &PlayerInventory) -> Self {
+        Self {
+            cards: inventory
+                .iter()
+                .cloned()
+                .enumerate()
+                .filter(|(_, card)| !card.is_leveraged())
+                .collect(),
+        }
+    }
+
+    /// Returns every minimal payment covering `price`, ranked from lowest to
+    /// highest cost. The result is empty if the price cannot be covered.
+    pub fn payments(&self, price: BidValue, cost: &impl PaymentCost) -> Vec<Payment> {
+        let mut picked = Vec::new();
+        let mut subsets = Vec::new();
+        self.collect(0, price, 0, &mut picked, &mut subsets);
+
+        let mut payments = subsets
+            .into_iter()
+            .map(|subset| {
+                let cards = subset.iter().map(|&i| self.cards[i].1).collect::<Vec<_>>();
+                let indices = subset.iter().map(|&i| self.cards[i].0).collect::<Vec<_>>();
+                Payment {
+                    choice: CardChoice::new(&indices),
+                    value: cards.iter().capital(),
+                    cost: cost.cost(&cards, price),
+                }
+            })
+            .collect::<Vec<_>>();
+        payments.sort_by(|a, b| a.cost.total_cmp(&b.cost));
+        payments
+    }
+
+    /// Returns the cheapest payment covering `price`, if any exists.
+    pub fn best(&self, price: BidValue, cost: &impl PaymentCost) -> Option<Payment> {
+        self.payments(price, cost).into_iter().next()
+    }
+
+    fn collect(
+        &self,
+        start: usize,
+        price: BidValue,
+        value: BidValue,
+        picked: &mut Vec<usize>,
+        subsets: &mut Vec<Vec<usize>>,
+    ) {
+        if value >= price {
+            // only keep the subset if every card is required to cover the price
+            let minimal = picked
+                .iter()
+                .all(|&i| value - self.cards[i].1.value() < price);
+            if minimal {
+                subsets.push(picked.clone());
+            }
+            return;
+        }
+        for i in start..self.cards.len() {
+            picked.push(i);
+            self.collect(
+                i + 1,
+                price,
+                value + self.cards[i].1.value(),
+                picked,
+                subsets,
+            );
+            picked.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GemArchtype;
+
+    /// Coins 1, 2 and 3, followed by an `AE` card worth 3.
+    fn inventory(leveraged_gem: bool) -> PlayerInventory {
+        let mut inventory = PlayerInventory::default();
+        inventory.push_back(Card::gem(GemArchtype::from_index(2)).with_leverage(leveraged_gem));
+        inventory
+    }
+
+    fn choices(payments: &[Payment]) -> Vec<Vec<usize>> {
+        let mut choices = payments
+            .iter()
+            .map(|payment| (0..4).filter(|&idx| payment.choice.check(idx)).collect())
+            .collect::<Vec<_>>();
+        choices.sort();
+        choices
+    }
+
+    #[test]
+    fn only_minimal_payments_are_listed() {
+        let optimizer = PaymentOptimizer::new(&inventory(false));
+        let payments = optimizer.payments(3, &MinimalOverpay);
+        assert_eq!(choices(&payments), [vec![0, 1], vec![2], vec![3]]);
+        let payments = optimizer.payments(7, &MinimalOverpay);
+        assert_eq!(choices(&payments), [vec![0, 2, 3], vec![1, 2, 3]]);
+        assert!(optimizer.payments(10, &MinimalOverpay).is_empty());
+    }
+
+    #[test]
+    fn leveraged_cards_are_never_paid() {
+        let optimizer = PaymentOptimizer::new(&inventory(true));
+        let payments = optimizer.payments(3, &MinimalOverpay);
+        assert_eq!(choices(&payments), [vec![0, 1], vec![2]]);
+        assert!(optimizer.payments(7, &MinimalOverpay).is_empty());
+    }
+
+    #[test]
+    fn cost_models_rank_payments() {
+        let optimizer = PaymentOptimizer::new(&inventory(false));
+        let best = |payment: Option<Payment>| choices(&[payment.unwrap()]).remove(0);
+        assert_eq!(best(optimizer.best(3, &KeepCoins)), [3]);
+        assert_ne!(best(optimizer.best(3, &ProtectMajorities::default())), [3]);
+
+        let overpay = optimizer.best(4, &MinimalOverpay).unwrap();
+        assert_eq!((overpay.value, overpay.cost), (4, 0.0));
+    }
+}
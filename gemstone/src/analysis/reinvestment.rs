@@ -0,0 +1,261 @@
+use crate::{BidValue, Card, CardChoice, CardIterator, GameInfo, GemNotation};
+
+use super::{MinimalOverpay, PaymentOptimizer};
+
+/// Weights used by the [`ReinvestmentPlanner`] to score flip sets.
+#[derive(Clone, Copy, Debug)]
+pub struct ReinvestmentWeights {
+    /// The score of a single gem point gained in this reinvestment.
+    pub point: f32,
+    /// The score of a single unit of capital, for each remaining round.
+    pub liquidity: f32,
+}
+
+impl Default for ReinvestmentWeights {
+    fn default() -> Self {
+        Self {
+            point: 1.0,
+            liquidity: 0.25,
+        }
+    }
+}
+
+/// An affordable set of cards to flip during the reinvestment phase.
+#[derive(Clone, Debug)]
+pub struct FlipPlan {
+    /// The cards to flip, relative to the inventory of the player.
+    pub choice: CardChoice,
+    /// The leveraged gem cards which become non-leveraged.
+    pub unleveraged: Vec<Card>,
+    /// The non-leveraged cards which are leveraged to pay for the flip.
+    pub payment: Vec<Card>,
+    /// The change in gem points caused by the flip.
+    pub points: i32,
+    /// The capital available to the player in the next round.
+    pub capital: BidValue,
+    /// The weighted score of the plan, where higher is better.
+    pub score: f32,
+    /// A short human-readable explanation of the plan.
+    pub explanation: String,
+}
+
+/// Lists every affordable flip set for a player during the reinvestment
+/// phase, scored by the gem points gained versus the capital kept for the
+/// remaining rounds.
+pub struct ReinvestmentPlanner<'a> {
+    info: &'a GameInfo,
+    player: usize,
+    weights: ReinvestmentWeights,
+}
+
+impl<'a> ReinvestmentPlanner<'a> {
+    pub fn new(info: &'a GameInfo, player: usize) -> Self {
+        Self {
+            info,
+            player,
+            weights: ReinvestmentWeights::default(),
+        }
+    }
+
+    pub fn with_weights(mut self, weights: ReinvestmentWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Returns the affordable flip sets ranked from best to worst. The first
+    /// plan is always at least as good as flipping nothing, which is included.
+    ///
+    /// Sets of leveraged cards which are dominated by another set of the same
+    /// price, gaining no more points and no more capital, are left out, as
+    /// they can never score higher.
+    pub fn plans(&self) -> Vec<FlipPlan> {
+        let inventory = self.info.inventory_at(self.player);
+        let optimizer = PaymentOptimizer::new(inventory);
+        let available = inventory.iter().capital();
+
+        let targets = inventory
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| card.is_leveraged() && !card.is_coin())
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        // the sets of targets worth considering, grouped by their price
+        let mut candidates: Vec<Vec<(i32, BidValue, Vec<usize>)>> =
+            vec![Vec::new(); available.max(0) as usize + 1];
+        for mask in 0..1_u32 << targets.len() {
+            let chosen = (0..targets.len())
+                .filter(|&bit| mask & (1 << bit) != 0)
+                .map(|bit| targets[bit])
+                .collect::<Vec<_>>();
+            let cards = chosen.iter().map(|&i| inventory.as_ref()[i]);
+            // un-leveraging a card costs its value minus one
+            let price = cards
+                .clone()
+                .map(|card| -card.scalar_value())
+                .sum::<BidValue>();
+            if price > available {
+                continue;
+            }
+            let gained = cards
+                .clone()
+                .map(|card| card.archtype().num_gems() as i32)
+                .sum::<i32>();
+            let value = cards.map(|card| card.value()).sum::<BidValue>();
+
+            let group = &mut candidates[price as usize];
+            if group
+                .iter()
+                .any(|&(points, capital, _)| points >= gained && capital >= value)
+            {
+                continue;
+            }
+            group.retain(|&(points, capital, _)| points > gained || capital > value);
+            group.push((gained, value, chosen));
+        }
+
+        let mut plans = Vec::new();
+        for (price, group) in candidates.iter().enumerate() {
+            if group.is_empty() {
+                continue;
+            }
+            // the payments only depend on the price, so they are found once
+            let payments = optimizer.payments(price as BidValue, &MinimalOverpay);
+            for payment in payments {
+                let paid = (0..inventory.len())
+                    .filter(|&i| payment.choice.check(i))
+                    .collect::<Vec<_>>();
+                for (_, _, chosen) in group {
+                    plans.push(self.plan(chosen, &paid));
+                }
+            }
+        }
+        plans.sort_by(|a, b| b.score.total_cmp(&a.score));
+        plans
+    }
+
+    /// Returns the highest scoring flip set.
+    pub fn best(&self) -> FlipPlan {
+        self.plans()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| self.plan(&[], &[]))
+    }
+
+    fn plan(&self, targets: &[usize], paid: &[usize]) -> FlipPlan {
+        let inventory = self.info.inventory_at(self.player);
+        let unleveraged = targets
+            .iter()
+            .map(|&i| inventory.as_ref()[i])
+            .collect::<Vec<_>>();
+        let payment = paid
+            .iter()
+            .map(|&i| inventory.as_ref()[i])
+            .collect::<Vec<_>>();
+
+        let gained = unleveraged
+            .iter()
+            .map(|card| card.archtype().num_gems() as i32)
+            .sum::<i32>();
+        let lost = payment
+            .iter()
+            .gem_cards()
+            .map(|card| card.archtype().num_gems() as i32)
+            .sum::<i32>();
+        let points = gained - lost;
+
+        // coin cards are reset after the reinvestment, so they are always available
+        let coins = inventory
+            .iter()
+            .coin_cards()
+            .map(|card| card.value())
+            .sum::<BidValue>();
+        let gems = inventory.iter().gem_cards().capital() - payment.iter().gem_cards().capital()
+            + unleveraged
+                .iter()
+                .map(|card| card.value())
+                .sum::<BidValue>();
+        let capital = coins + gems;
+
+        let remaining = 5_usize.saturating_sub(self.info.round_index());
+        let score = points as f32 * self.weights.point
+            + capital as f32 * self.weights.liquidity * remaining as f32;
+
+        let indices = targets.iter().chain(paid).cloned().collect::<Vec<_>>();
+        FlipPlan {
+            choice: CardChoice::new(&indices),
+            explanation: Self::explain(&unleveraged, &payment, points, capital, remaining),
+            unleveraged,
+            payment,
+            points,
+            capital,
+            score,
+        }
+    }
+
+    fn explain(
+        unleveraged: &[Card],
+        payment: &[Card],
+        points: i32,
+        capital: BidValue,
+        remaining: usize,
+    ) -> String {
+        let format = |cards: &[Card]| {
+            cards
+                .iter()
+                .map(|&card| GemNotation::format_card(card))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let action = match (unleveraged.is_empty(), payment.is_empty()) {
+            (true, _) => "flip nothing".to_string(),
+            (false, true) => format!("flip {} for free", format(unleveraged)),
+            (false, false) => format!(
+                "flip {} paying with {}",
+                format(unleveraged),
+                format(payment)
+            ),
+        };
+        format!("{action}: {points:+} points, capital {capital} for {remaining} remaining rounds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(notation: &str) -> GameInfo {
+        notation.parse::<GemNotation>().unwrap().to_info().unwrap()
+    }
+
+    #[test]
+    fn dominated_flip_sets_are_left_out() {
+        // AE and AS cost the same and gain the same, so only one is kept
+        let info = position("-//cf123!AEASD;123;123");
+        let plans = ReinvestmentPlanner::new(&info, 0).plans();
+        assert_eq!(plans.len(), 11);
+        assert!(plans.iter().any(|plan| plan.unleveraged.is_empty()));
+        let flips = |plan: &FlipPlan| {
+            plan.unleveraged
+                .iter()
+                .map(|&card| GemNotation::format_card(card))
+                .collect::<Vec<_>>()
+        };
+        assert!(plans.iter().all(|plan| flips(plan) != ["AS"]));
+        assert!(plans.iter().any(|plan| flips(plan) == ["AE", "AS", "D"]));
+    }
+
+    #[test]
+    fn best_plan_weighs_points_against_capital() {
+        let info = position("-//cf123!AEASD;123;123");
+        let best = |point, liquidity| {
+            ReinvestmentPlanner::new(&info, 0)
+                .with_weights(ReinvestmentWeights { point, liquidity })
+                .best()
+        };
+        assert!(best(0.0, 1.0).unleveraged.len() == 3);
+        let hoarding = best(0.0, -1.0);
+        assert!(hoarding.unleveraged.is_empty());
+        assert_eq!(hoarding.capital, 6);
+    }
+}
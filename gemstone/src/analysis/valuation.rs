@@ -0,0 +1,221 @@
+use crate::{BidValue, Card, CardIterator, GameInfo, GemCount, GemType};
+
+/// Weights used by the [`BidValuation`] to value the cards on the stack.
+#[derive(Clone, Copy, Debug)]
+pub struct ValuationWeights {
+    /// The value of a single gem.
+    pub gem: f32,
+    /// The value of holding the majority of a gem type alone.
+    pub sole_majority: f32,
+    /// The value of sharing the majority of a gem type.
+    pub shared_majority: f32,
+    /// How much of the denial value is added to the total value of a card.
+    pub denial: f32,
+    /// The number of capital units a player should bid per unit of value.
+    pub exchange_rate: f32,
+}
+
+impl Default for ValuationWeights {
+    fn default() -> Self {
+        Self {
+            gem: 1.0,
+            sole_majority: 3.0,
+            shared_majority: 2.0,
+            denial: 0.5,
+            exchange_rate: 1.0,
+        }
+    }
+}
+
+/// The marginal value of a single card on the stack to a single player.
+#[derive(Clone, Copy, Debug)]
+pub struct CardValue {
+    pub card: Card,
+    /// The value of the gems on the card.
+    pub direct: f32,
+    /// The change in the majority standings of the player.
+    pub majority: f32,
+    /// The largest majority gain any opponent would get from the card, and
+    /// therefore the value of keeping it from them.
+    pub denial: f32,
+}
+
+impl CardValue {
+    /// Returns the combined value of the card given the weights it was
+    /// computed with.
+    pub fn total(&self, weights: &ValuationWeights) -> f32 {
+        self.direct + self.majority + self.denial * weights.denial
+    }
+}
+
+/// Computes the marginal value of each card on the stack for each player.
+///
+/// Gems are counted as if every card was non-leveraged, since a bought card
+/// can always be un-leveraged in a later reinvestment phase.
+pub struct BidValuation<'a> {
+    info: &'a GameInfo,
+    weights: ValuationWeights,
+    /// The number of gems of each [`GemType`] owned by each player.
+    counts: Vec<[u8; 6]>,
+}
+
+impl<'a> BidValuation<'a> {
+    pub fn new(info: &'a GameInfo) -> Self {
+        Self {
+            info,
+            weights: ValuationWeights::default(),
+            counts: (0..info.num_players())
+                .map(|player| info.majorities().counts(player).map(GemCount::total))
+                .collect(),
+        }
+    }
+
+    pub fn with_weights(mut self, weights: ValuationWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    #[inline]
+    pub fn weights(&self) -> &ValuationWeights {
+        &self.weights
+    }
+
+    /// Returns the value of the stack card at `card_idx` to the given player.
+    pub fn value(&self, card_idx: usize, player: usize) -> CardValue {
+        let card = self.info.stack().as_ref()[card_idx];
+        let denial = (0..self.info.num_players())
+            .filter(|&other| other != player)
+            .map(|other| self.majority_gain(card, other))
+            .fold(0.0, f32::max);
+
+        CardValue {
+            card,
+            direct: card.archtype().num_gems() as f32 * self.weights.gem,
+            majority: self.majority_gain(card, player),
+            denial,
+        }
+    }
+
+    /// Returns the value of every card on the stack to the given player.
+    pub fn values(&self, player: usize) -> Vec<CardValue> {
+        (0..self.info.stack_size())
+            .map(|idx| self.value(idx, player))
+            .collect()
+    }
+
+    /// Returns the value of every card on the stack to every player, indexed
+    /// first by player and then by stack index.
+    pub fn table(&self) -> Vec<Vec<CardValue>> {
+        (0..self.info.num_players())
+            .map(|player| self.values(player))
+            .collect()
+    }
+
+    /// Returns the index of the most valuable stack card to the given player.
+    pub fn best_card(&self, player: usize) -> Option<usize> {
+        self.values(player)
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total(&self.weights).total_cmp(&b.total(&self.weights)))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Returns the highest bid the player should make, given that the highest
+    /// bidder picks any card on the stack. The bid never exceeds the capital
+    /// of the player.
+    pub fn max_bid(&self, player: usize) -> BidValue {
+        let value = self
+            .values(player)
+            .iter()
+            .map(|value| value.total(&self.weights))
+            .fold(0.0, f32::max);
+        let capital = self.info.inventory_at(player).iter().capital();
+        ((value * self.weights.exchange_rate).floor() as BidValue).clamp(0, capital)
+    }
+
+    fn majority_gain(&self, card: Card, player: usize) -> f32 {
+        let mut counts = self.counts.clone();
+        card.archtype()
+            .gems()
+            .for_each(|gem| counts[player][gem as usize] += 1);
+
+        GemType::iter()
+            .filter(|&gem| card.archtype().gems().any(|other| other == gem))
+            .map(|gem| {
+                self.standing(&counts, player, gem) - self.standing(&self.counts, player, gem)
+            })
+            .sum()
+    }
+
+    fn standing(&self, counts: &[[u8; 6]], player: usize, gem: GemType) -> f32 {
+        let idx = gem as usize;
+        let own = counts[player][idx];
+        let holders = counts.iter().filter(|count| count[idx] == own).count();
+        let best = counts.iter().map(|count| count[idx]).max().unwrap_or(0);
+        match own {
+            0 => 0.0,
+            _ if own < best => 0.0,
+            _ if holders == 1 => self.weights.sole_majority,
+            _ => self.weights.shared_majority,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GemNotation;
+
+    /// The first player already holds an `AE` card, and `SE` and `D` are left
+    /// on the stack.
+    fn position() -> GameInfo {
+        "-/!SED/12!3AE;cf123;123"
+            .parse::<GemNotation>()
+            .unwrap()
+            .to_info()
+            .unwrap()
+    }
+
+    #[test]
+    fn values_count_gems_majorities_and_denial() {
+        let info = position();
+        let valuation = BidValuation::new(&info);
+
+        // sapphire becomes a sole majority, emerald stays one
+        let value = valuation.value(0, 0);
+        assert_eq!(
+            (value.direct, value.majority, value.denial),
+            (2.0, 3.0, 5.0)
+        );
+        // an opponent also comes to share the emerald majority
+        let value = valuation.value(0, 1);
+        assert_eq!(
+            (value.direct, value.majority, value.denial),
+            (2.0, 5.0, 5.0)
+        );
+        let value = valuation.value(1, 2);
+        assert_eq!(
+            (value.direct, value.majority, value.denial),
+            (1.0, 3.0, 3.0)
+        );
+
+        assert_eq!(valuation.table().len(), 3);
+        assert_eq!(valuation.best_card(0), Some(0));
+    }
+
+    #[test]
+    fn max_bid_is_capped_by_capital() {
+        let info = position();
+        let valuation = BidValuation::new(&info);
+        let capital = info.inventory_at(0).iter().capital();
+        assert_eq!(valuation.max_bid(0), 7.min(capital));
+        assert_eq!(valuation.max_bid(1), 6);
+
+        let weights = ValuationWeights {
+            exchange_rate: 0.5,
+            ..Default::default()
+        };
+        let valuation = BidValuation::new(&info).with_weights(weights);
+        assert_eq!(valuation.max_bid(1), 4);
+    }
+}
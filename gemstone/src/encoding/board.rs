@@ -0,0 +1,236 @@
+use std::io::IsTerminal;
+
+use crate::{Card, CardIterator, GameInfo, GemType, Majority};
+
+use super::notation::GemNotation;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const REVERSE: &str = "\x1b[7m";
+
+/// Lays out a [`GameInfo`] as a human-readable board: the round and phase,
+/// the stack, and the coins, gems, majorities and score of every player.
+///
+/// Leveraged cards are written in parentheses, the player to decide is
+/// marked by `>` and the highest bidder by `*`. With colours enabled, gems
+/// are coloured by [`GemType`], leveraged cards are dimmed and the highest
+/// bidder is highlighted.
+#[derive(Clone, Debug, Default)]
+pub struct BoardRenderer {
+    colours: bool,
+    names: Vec<String>,
+}
+
+impl BoardRenderer {
+    /// Creates a renderer writing plain text without escape codes.
+    pub fn plain() -> Self {
+        Self::default()
+    }
+
+    /// Creates a renderer writing ANSI colours.
+    pub fn ansi() -> Self {
+        Self {
+            colours: true,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a renderer writing colours only if the stream is a terminal
+    /// and the `NO_COLOR` environment variable is not set.
+    pub fn for_stream(stream: &impl IsTerminal) -> Self {
+        match stream.is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+            true => Self::ansi(),
+            false => Self::plain(),
+        }
+    }
+
+    /// Names the players in seat order. Players without a name are called
+    /// `Player 1`, `Player 2` and so on.
+    pub fn with_names(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn render(&self, info: &GameInfo) -> String {
+        let mut lines = vec![self.bold(&Self::heading(info))];
+
+        let stack = info
+            .stack()
+            .iter()
+            .map(|&card| self.card(card.with_leverage(false)))
+            .collect::<Vec<_>>();
+        if !stack.is_empty() {
+            lines.push(format!("Stack: {}", stack.join(" ")));
+        }
+        if info.is_auction_phase() && info.highest_bid() >= 0 {
+            lines.push(format!(
+                "Highest bid: {} by {}",
+                info.highest_bid(),
+                self.name(info.highest_bidder())
+            ));
+        }
+
+        let scores = info.scores();
+        let width = (0..info.num_players())
+            .map(|player| self.name(player).len())
+            .max()
+            .unwrap_or(0);
+        for player in 0..info.num_players() {
+            let inventory = info.inventory_at(player);
+            let decides = matches!(info.next_decision(), Some((next, _)) if next == player);
+            let highest = info.is_auction_phase()
+                && info.highest_bid() >= 0
+                && info.highest_bidder() == player;
+
+            let name = format!("{:<width$}", self.name(player));
+            let name = match highest {
+                true => self.style(REVERSE, &name),
+                false => self.bold(&name),
+            };
+            lines.push(format!(
+                "{} {name} {} capital {:>2}, score {:>2}",
+                if decides { ">" } else { " " },
+                if highest { "*" } else { " " },
+                inventory.iter().capital(),
+                scores.get(player)
+            ));
+
+            let coins = inventory.iter().cloned().coin_cards().collect::<Vec<_>>();
+            let gems = inventory.iter().cloned().gem_cards().collect::<Vec<_>>();
+            lines.push(format!("    coins  {}", self.cards(&coins)));
+            lines.push(format!("    gems   {}", self.cards(&gems)));
+
+            let majorities = GemType::iter()
+                .filter_map(|gem| match info.majorities().majority(gem) {
+                    Majority::Owned(holder) if holder == player => {
+                        Some(self.gem(gem, &format!("{gem:?}")))
+                    }
+                    Majority::Shared(holders) if holders.contains(&player) => {
+                        Some(format!("{} (shared)", self.gem(gem, &format!("{gem:?}"))))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if !majorities.is_empty() {
+                lines.push(format!("    majorities  {}", majorities.join(", ")));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn heading(info: &GameInfo) -> String {
+        if info.game_over() {
+            return "Game over".to_string();
+        }
+        let phase = match info.is_auction_phase() {
+            true => "auction",
+            false => "reinvestment",
+        };
+        format!("Round {} of 6, {phase} phase", info.round_index() + 1)
+    }
+
+    fn name(&self, player: usize) -> String {
+        match self.names.get(player) {
+            Some(name) => name.clone(),
+            None => format!("Player {}", player + 1),
+        }
+    }
+
+    fn cards(&self, cards: &[Card]) -> String {
+        match cards.is_empty() {
+            true => "-".to_string(),
+            false => cards
+                .iter()
+                .map(|&card| self.card(card))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// Writes the code of a card, colouring every gem letter by its type.
+    fn card(&self, card: Card) -> String {
+        let dim = match card.is_leveraged() {
+            true => DIM,
+            false => "",
+        };
+        let code = match card.is_coin() {
+            true => self.style(dim, &card.value().to_string()),
+            false => GemNotation::format_card(card)
+                .chars()
+                .zip(card.archtype().gems())
+                .map(|(letter, gem)| {
+                    self.style(&format!("{dim}{}", Self::colour(gem)), &letter.to_string())
+                })
+                .collect(),
+        };
+        match card.is_leveraged() {
+            true => format!("{}{code}{}", self.style(DIM, "("), self.style(DIM, ")")),
+            false => code,
+        }
+    }
+
+    fn gem(&self, gem: GemType, text: &str) -> String {
+        self.style(Self::colour(gem), text)
+    }
+
+    fn colour(gem: GemType) -> &'static str {
+        match gem {
+            GemType::Amethyst => "\x1b[35m",
+            GemType::Diamond => "\x1b[97m",
+            GemType::Emerald => "\x1b[32m",
+            GemType::Ruby => "\x1b[31m",
+            GemType::Sapphire => "\x1b[34m",
+            GemType::Topaz => "\x1b[33m",
+        }
+    }
+
+    fn bold(&self, text: &str) -> String {
+        self.style(BOLD, text)
+    }
+
+    fn style(&self, code: &str, text: &str) -> String {
+        match self.colours && !code.is_empty() {
+            true => format!("{code}{text}{RESET}"),
+            false => text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSITION: &str = "3/EEASRR/c123AE;fh12!3D;123SE";
+
+    fn render(renderer: BoardRenderer) -> String {
+        let info = POSITION.parse::<GemNotation>().unwrap().to_info().unwrap();
+        renderer.with_names(["Ann", "Bob"]).render(&info)
+    }
+
+    #[test]
+    fn plain_board_marks_leverage_players_and_majorities() {
+        let expected = "\
+Round 2 of 6, auction phase
+Stack: EE AS RR
+Highest bid: 3 by Bob
+> Ann        capital  9, score  7
+    coins  1 2 3
+    gems   AE
+    majorities  Amethyst, Emerald (shared)
+  Bob      * capital  3, score  0
+    coins  1 2 (3)
+    gems   (D)
+  Player 3   capital  9, score  7
+    coins  1 2 3
+    gems   SE
+    majorities  Emerald (shared), Sapphire";
+        assert_eq!(render(BoardRenderer::plain()), expected);
+    }
+
+    #[test]
+    fn only_ansi_board_has_escape_codes() {
+        assert!(!render(BoardRenderer::plain()).contains('\x1b'));
+        assert!(render(BoardRenderer::ansi()).contains('\x1b'));
+    }
+}
@@ -0,0 +1,336 @@
+//! A versioned encoding of a [`GameInfo`] into a flat vector of `f32`
+//! features, seen from the perspective of a single seat.
+//!
+//! # Specification (version 1)
+//!
+//! Seats are ordered clockwise relative to the observing player, such that
+//! relative seat 0 is the observer and relative seat 1 the next player. The
+//! vector holds [`FeatureEncoder::LEN`] features in the following order:
+//!
+//! | offset | len | feature                                                    |
+//! |--------|-----|------------------------------------------------------------|
+//! | 0      | 144 | four seat blocks of 36 features, see below                 |
+//! | 144    | 64  | four stack slots, each a one-hot over the 16 archtypes     |
+//! | 208    | 1   | highest bid divided by ten, or zero if nobody has bid      |
+//! | 209    | 1   | one if any bid has been made                               |
+//! | 210    | 4   | one-hot relative seat of the highest bidder, if any bid    |
+//! | 214    | 4   | one-hot relative seat of the player to decide, if any      |
+//! | 218    | 6   | one-hot round index, all zero once the game has ended      |
+//! | 224    | 3   | one-hot next decision: bid, pick card, reinvest            |
+//! | 227    | 16  | number of unseen cards of each archtype                    |
+//! | 243    | 3   | one-hot number of players: two, three, four                |
+//!
+//! Each seat block holds the following features, and is all zero for seats
+//! not taking part in the game:
+//!
+//! | offset | len | feature                                                    |
+//! |--------|-----|------------------------------------------------------------|
+//! | 0      | 16  | number of non-leveraged gem cards of each archtype         |
+//! | 16     | 16  | number of leveraged gem cards of each archtype             |
+//! | 32     | 3   | one if the coin card of value 1, 2 and 3 is non-leveraged  |
+//! | 35     | 1   | one, marking the seat as taking part                       |
+//!
+//! Archtypes are ordered by [`GemArchtype::index`](crate::GemArchtype::index).
+//! Unseen cards are those neither in the stack nor in any inventory. Any
+//! change to this layout must increment [`FeatureEncoder::VERSION`].
+
+use std::io::{self, Write};
+
+use crate::{Card, CardIterator, Decision, GameInfo};
+
+use super::notation::ARCHTYPE_CODES;
+
+const SEAT_LEN: usize = 36;
+const MAGIC: &[u8; 4] = b"GEMF";
+
+/// Encodes a [`GameInfo`] as described in the [module documentation](self).
+pub struct FeatureEncoder;
+
+impl FeatureEncoder {
+    /// The version of the feature layout.
+    pub const VERSION: u32 = 1;
+    /// The number of features in a single encoded position.
+    pub const LEN: usize = 246;
+
+    /// Encodes the game from the perspective of `player`.
+    pub fn encode(info: &GameInfo, player: usize) -> Vec<f32> {
+        let mut features = Vec::with_capacity(Self::LEN);
+        let num_players = info.num_players();
+        let relative = |seat: usize| (seat + num_players - player) % num_players;
+        let one_hot = |features: &mut Vec<f32>, len: usize, idx: Option<usize>| {
+            features.extend((0..len).map(|i| f32::from(Some(i) == idx)));
+        };
+
+        for offset in 0..4 {
+            if offset >= num_players {
+                features.extend([0.0; SEAT_LEN]);
+                continue;
+            }
+            let inventory = info.inventory_at((player + offset) % num_players);
+            for leveraged in [false, true] {
+                let mut counts = [0.0; 16];
+                inventory
+                    .iter()
+                    .gem_cards()
+                    .filter(|card| card.is_leveraged() == leveraged)
+                    .for_each(|card| counts[card.archtype().index() as usize] += 1.0);
+                features.extend(counts);
+            }
+            for value in 1..=3 {
+                let available = inventory
+                    .iter()
+                    .coin_cards()
+                    .any(|card| card.value() == value && !card.is_leveraged());
+                features.push(f32::from(available));
+            }
+            features.push(1.0);
+        }
+
+        for slot in 0..4 {
+            let archtype = (slot < info.stack_size())
+                .then(|| info.stack().as_ref()[slot].archtype().index() as usize);
+            one_hot(&mut features, 16, archtype);
+        }
+
+        let bid = info.highest_bid();
+        features.push(bid.max(0) as f32 / 10.0);
+        features.push(f32::from(bid >= 0));
+        one_hot(
+            &mut features,
+            4,
+            (bid >= 0).then(|| relative(info.highest_bidder())),
+        );
+        let next = info.next_decision();
+        one_hot(&mut features, 4, next.map(|(seat, _)| relative(seat)));
+        one_hot(
+            &mut features,
+            6,
+            (!info.game_over()).then(|| info.round_index()),
+        );
+        let decision = next.map(|(_, decision)| match decision {
+            Decision::Bid => 0,
+            Decision::PickCard => 1,
+            Decision::Reinvest => 2,
+        });
+        one_hot(&mut features, 3, decision);
+
+        let mut unseen = [0.0; 16];
+        Card::gem_deck()
+            .iter()
+            .for_each(|card| unseen[card.archtype().index() as usize] += 1.0);
+        info.inventories()
+            .iter()
+            .flat_map(|inventory| inventory.iter().gem_cards())
+            .chain(info.stack().iter())
+            .for_each(|card| unseen[card.archtype().index() as usize] -= 1.0);
+        features.extend(unseen);
+
+        one_hot(&mut features, 3, Some(num_players - 2));
+        debug_assert_eq!(features.len(), Self::LEN);
+        features
+    }
+
+    /// Returns a short name for every feature, in encoding order.
+    pub fn names() -> Vec<String> {
+        let mut names = Vec::with_capacity(Self::LEN);
+        for seat in 0..4 {
+            for prefix in ["gem", "lev"] {
+                names.extend(ARCHTYPE_CODES.map(|code| format!("s{seat}_{prefix}_{code}")));
+            }
+            names.extend((1..=3).map(|value| format!("s{seat}_coin{value}")));
+            names.push(format!("s{seat}_present"));
+        }
+        for slot in 0..4 {
+            names.extend(ARCHTYPE_CODES.map(|code| format!("stack{slot}_{code}")));
+        }
+        names.push("highest_bid".to_string());
+        names.push("has_bid".to_string());
+        names.extend((0..4).map(|seat| format!("bidder_s{seat}")));
+        names.extend((0..4).map(|seat| format!("next_s{seat}")));
+        names.extend((0..6).map(|round| format!("round{round}")));
+        names.extend(["decision_bid", "decision_pick", "decision_reinvest"].map(String::from));
+        names.extend(ARCHTYPE_CODES.map(|code| format!("unseen_{code}")));
+        names.extend((2..=4).map(|players| format!("players{players}")));
+        names
+    }
+}
+
+/// The file format written by a [`FeatureWriter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header of feature and target names,
+    /// preceded by a comment line holding the encoding version.
+    Csv,
+    /// The magic bytes `GEMF`, followed by the encoding version, the number of
+    /// features and the number of targets as little-endian `u32`. Every row
+    /// follows as little-endian `f32` features and then targets.
+    Binary,
+}
+
+/// Writes encoded positions together with training targets, such as the
+/// final score of the observing player, in bulk.
+pub struct FeatureWriter<W: Write> {
+    writer: W,
+    format: ExportFormat,
+    targets: usize,
+    rows: usize,
+}
+
+impl<W: Write> FeatureWriter<W> {
+    /// Creates a writer and writes the header, where every row holds the
+    /// given number of targets.
+    pub fn new(mut writer: W, format: ExportFormat, targets: usize) -> io::Result<Self> {
+        match format {
+            ExportFormat::Csv => {
+                writeln!(writer, "# gem features v{}", FeatureEncoder::VERSION)?;
+                let mut names = FeatureEncoder::names();
+                names.extend((0..targets).map(|target| format!("target{target}")));
+                writeln!(writer, "{}", names.join(","))?;
+            }
+            ExportFormat::Binary => {
+                writer.write_all(MAGIC)?;
+                writer.write_all(&FeatureEncoder::VERSION.to_le_bytes())?;
+                writer.write_all(&(FeatureEncoder::LEN as u32).to_le_bytes())?;
+                writer.write_all(&(targets as u32).to_le_bytes())?;
+            }
+        }
+        Ok(Self {
+            writer,
+            format,
+            targets,
+            rows: 0,
+        })
+    }
+
+    /// Writes a single row of encoded features and targets. This function
+    /// will return an error if either has the wrong length.
+    pub fn write(&mut self, features: &[f32], targets: &[f32]) -> io::Result<()> {
+        if features.len() != FeatureEncoder::LEN || targets.len() != self.targets {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "expected {} features and {} targets, got {} and {}",
+                    FeatureEncoder::LEN,
+                    self.targets,
+                    features.len(),
+                    targets.len()
+                ),
+            ));
+        }
+        let values = features.iter().chain(targets);
+        match self.format {
+            ExportFormat::Csv => {
+                let row = values.map(f32::to_string).collect::<Vec<_>>().join(",");
+                writeln!(self.writer, "{row}")?;
+            }
+            ExportFormat::Binary => {
+                for value in values {
+                    self.writer.write_all(&value.to_le_bytes())?;
+                }
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Encodes the game from the perspective of `player` and writes it
+    /// together with the targets.
+    pub fn write_position(
+        &mut self,
+        info: &GameInfo,
+        player: usize,
+        targets: &[f32],
+    ) -> io::Result<()> {
+        self.write(&FeatureEncoder::encode(info, player), targets)
+    }
+
+    /// Returns the number of rows written.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::GemNotation;
+
+    /// Returns the features of a position from the perspective of `player`,
+    /// keyed by feature name.
+    fn encode(notation: &str, player: usize) -> Vec<(String, f32)> {
+        let info = notation.parse::<GemNotation>().unwrap().to_info().unwrap();
+        FeatureEncoder::names()
+            .into_iter()
+            .zip(FeatureEncoder::encode(&info, player))
+            .collect()
+    }
+
+    fn nonzero(features: &[(String, f32)]) -> Vec<(&str, f32)> {
+        features
+            .iter()
+            .filter(|(_, value)| *value != 0.0)
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn names_are_unique_and_match_the_length() {
+        let names = FeatureEncoder::names();
+        assert_eq!(names.len(), FeatureEncoder::LEN);
+        assert_eq!(names.iter().collect::<HashSet<_>>().len(), names.len());
+    }
+
+    #[test]
+    fn positions_are_encoded_from_the_perspective_of_a_seat() {
+        // the first player holds a leveraged `AE` and a leveraged coin, and
+        // the second player is about to bid on `SE` and `D`
+        let features = encode("-/!SED/12!3AE;cf123;123", 1);
+        let unseen = ARCHTYPE_CODES
+            .iter()
+            .filter(|&&code| !["AE", "SE"].contains(&code))
+            .map(|code| format!("unseen_{code}"))
+            .collect::<Vec<_>>();
+
+        let mut expected = vec![
+            ("s0_coin1", 1.0),
+            ("s0_coin2", 1.0),
+            ("s0_coin3", 1.0),
+            ("s0_present", 1.0),
+            ("s1_coin1", 1.0),
+            ("s1_coin2", 1.0),
+            ("s1_coin3", 1.0),
+            ("s1_present", 1.0),
+            ("s2_lev_AE", 1.0),
+            ("s2_coin1", 1.0),
+            ("s2_coin2", 1.0),
+            ("s2_present", 1.0),
+            ("stack0_SE", 1.0),
+            ("stack1_D", 1.0),
+            ("next_s0", 1.0),
+            ("round0", 1.0),
+            ("decision_bid", 1.0),
+        ];
+        for name in &unseen {
+            expected.push((name, if name == "unseen_D" { 2.0 } else { 1.0 }));
+        }
+        expected.push(("players3", 1.0));
+        assert_eq!(nonzero(&features), expected);
+
+        // the same position seen by the first player moves its seat block
+        // and the player to decide
+        let features = encode("-/!SED/12!3AE;cf123;123", 0);
+        let nonzero = nonzero(&features);
+        assert!(nonzero.contains(&("s0_lev_AE", 1.0)));
+        assert!(nonzero.contains(&("next_s1", 1.0)));
+        assert!(!nonzero.contains(&("s0_coin3", 1.0)));
+    }
+}
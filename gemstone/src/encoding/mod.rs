@@ -1,3 +1,11 @@
+mod board;
+mod features;
+mod narrator;
 mod notation;
+mod transcript;
 
-pub use notation::GemNotation;
+pub use board::BoardRenderer;
+pub use features::{ExportFormat, FeatureEncoder, FeatureWriter};
+pub use narrator::Narrator;
+pub use notation::{GemNotation, ARCHTYPE_CODES};
+pub use transcript::{Transcript, TranscriptLine};
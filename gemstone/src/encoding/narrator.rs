@@ -0,0 +1,261 @@
+use crate::{Card, GameEvent, GameInfo};
+
+use super::notation::GemNotation;
+
+const ORDINALS: [&str; 4] = ["first", "second", "third", "fourth"];
+
+/// Narrates the steps of a game in English, such as "Second player bids 3."
+/// or "Third player pays with coin 1, coin 3.".
+///
+/// Players are called by their seat, such as "first player", unless they are
+/// given names.
+#[derive(Clone, Debug, Default)]
+pub struct Narrator {
+    names: Vec<String>,
+}
+
+impl Narrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names the players in seat order.
+    pub fn with_names(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Narrates the start of the game.
+    pub fn narrate_start(&self, info: &GameInfo) -> Vec<String> {
+        let mut sentences = vec![format!(
+            "the game starts with {} players",
+            info.num_players()
+        )];
+        sentences.push(format!(
+            "cards are drawn: {}",
+            Self::cards(info.stack().as_ref(), false)
+        ));
+        sentences.push(format!("{} starts", self.name(info.starting_player())));
+        Self::finish(sentences)
+    }
+
+    /// Narrates a single step of the game, from the position before the step
+    /// to the position after it. Returns nothing if no decision was made.
+    pub fn narrate(&self, before: &GameInfo, after: &GameInfo) -> Vec<String> {
+        let mut sentences = Vec::new();
+        let start = before.history().len().min(after.history().len());
+        for event in after.history().iter().skip(start) {
+            self.narrate_event(event, &mut sentences);
+        }
+        if sentences.is_empty() {
+            return sentences;
+        }
+
+        if after.round_index() > before.round_index() {
+            sentences.push("all coin cards become non-leveraged".to_string());
+            if after.game_over() {
+                sentences.push("the game is over".to_string());
+                let scores = after.scores();
+                for player in 0..after.num_players() {
+                    sentences.push(format!(
+                        "{} scores {}",
+                        self.name(player),
+                        scores.get(player)
+                    ));
+                }
+            } else {
+                sentences.push(format!(
+                    "round {} begins and new cards are drawn: {}",
+                    after.round_index() + 1,
+                    Self::cards(after.stack().as_ref(), false)
+                ));
+                sentences.push(format!(
+                    "{} becomes the starting bidder",
+                    self.name(after.starting_player())
+                ));
+            }
+        } else if before.is_auction_phase() && after.is_reinvestment_phase() {
+            sentences.push(format!(
+                "{} becomes the starting reinvester",
+                self.name(after.starting_player())
+            ));
+        } else if before.stack_size() > after.stack_size() {
+            sentences.push(format!(
+                "{} becomes the starting bidder",
+                self.name(after.starting_player())
+            ));
+        }
+        Self::finish(sentences)
+    }
+
+    fn narrate_event(&self, event: &GameEvent, sentences: &mut Vec<String>) {
+        let name = self.name(event.player());
+        match event {
+            &GameEvent::Bid {
+                bid,
+                highest,
+                capital,
+                ..
+            } => {
+                if bid > highest {
+                    sentences.push(format!("{name} bids {bid}"));
+                    sentences.push(format!("{name} becomes the highest bidder"));
+                } else if capital <= highest {
+                    sentences.push(format!("{name} is forced to pass"));
+                } else {
+                    sentences.push(format!("{name} passes"));
+                }
+            }
+            GameEvent::Purchase { card, payment, .. } => {
+                let payment = match payment.is_empty() {
+                    true => "nothing".to_string(),
+                    false => Self::cards(payment, false),
+                };
+                sentences.push(format!("{name} pays with {payment}"));
+                sentences.push(format!("{name} buys {}", GemNotation::format_card(*card)));
+            }
+            GameEvent::Reinvestment { flipped, .. } => {
+                sentences.push(match flipped.is_empty() {
+                    true => format!("{name} flips nothing"),
+                    false => format!("{name} flips {}", Self::cards(flipped, true)),
+                });
+            }
+        }
+    }
+
+    fn name(&self, player: usize) -> String {
+        match self.names.get(player) {
+            Some(name) => name.clone(),
+            None => format!("{} player", ORDINALS[player]),
+        }
+    }
+
+    /// Lists cards as `coin 1` or by their code, with `!` before leveraged
+    /// cards if `leverage` is set.
+    fn cards(cards: &[Card], leverage: bool) -> String {
+        cards
+            .iter()
+            .map(|&card| {
+                let prefix = if leverage && card.is_leveraged() {
+                    "!"
+                } else {
+                    ""
+                };
+                match card.is_coin() {
+                    true => format!("{prefix}coin {}", card.value()),
+                    false => format!("{prefix}{}", GemNotation::format_card(card)),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Capitalizes the sentences and ends them with a full stop.
+    fn finish(sentences: Vec<String>) -> Vec<String> {
+        sentences
+            .into_iter()
+            .map(|sentence| {
+                let mut chars = sentence.chars();
+                match chars.next() {
+                    Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+                    None => sentence,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::transcript::{tests::example, Transcript, TranscriptLine};
+
+    /// Narrates every step of the annotated example at the top of
+    /// `notation.rs`, finding the moves which lead from each position to the
+    /// next.
+    fn narrate_example(narrator: &Narrator) -> Vec<Vec<String>> {
+        let transcript = example().parse::<Transcript>().unwrap();
+        let positions = transcript
+            .lines()
+            .iter()
+            .skip_while(|line| *line == &TranscriptLine::Break)
+            .map_while(|line| match line {
+                TranscriptLine::Break => None,
+                line => Some(line),
+            })
+            .filter_map(|line| match line {
+                TranscriptLine::Position(notation) => Some(notation),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        positions
+            .windows(2)
+            .map(|pair| {
+                let before = pair[0].to_info().unwrap();
+                let mut after = before.clone();
+                for step in Transcript::find_step(&before, pair[1])
+                    .unwrap_or_else(|| panic!("`{}` does not follow", pair[1]))
+                {
+                    step.apply(&mut after).unwrap();
+                }
+                narrator.narrate(&before, &after)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn narrates_the_notation_example() {
+        let steps = narrate_example(&Narrator::new());
+        assert_eq!(
+            steps[0],
+            [
+                "First player bids 3.",
+                "First player becomes the highest bidder."
+            ]
+        );
+        assert_eq!(steps[1], ["Second player passes."]);
+        assert_eq!(
+            steps[2],
+            [
+                "Third player passes.",
+                "First player pays with coin 3.",
+                "First player buys AE.",
+                "Second player becomes the starting bidder."
+            ]
+        );
+        assert_eq!(
+            steps[4],
+            [
+                "Third player bids 4.",
+                "Third player becomes the highest bidder."
+            ]
+        );
+        assert_eq!(
+            steps[5],
+            [
+                "First player is forced to pass.",
+                "Third player pays with coin 1, coin 3.",
+                "Third player buys SE.",
+                "First player becomes the starting bidder."
+            ]
+        );
+        assert_eq!(
+            steps[8],
+            [
+                "Third player is forced to pass.",
+                "Second player pays with coin 2.",
+                "Second player buys D.",
+                "Second player becomes the starting reinvester."
+            ]
+        );
+        assert_eq!(steps[9], ["Second player flips coin 1, !D."]);
+        assert_eq!(steps[11][0], "First player flips coin 2, !AE.");
+    }
+
+    #[test]
+    fn narrates_with_names() {
+        let steps = narrate_example(&Narrator::new().with_names(["Ann", "Bob", "Cid"]));
+        assert_eq!(steps[2][0], "Cid passes.");
+        assert_eq!(steps[10], ["Cid flips coin 2, !SE."]);
+    }
+}
@@ -64,16 +64,29 @@
 -/EEASRR/123AE;123D;cf123SE
 */
 
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use crate::{
-    game::{Card, GameInfo},
+    errors::{GemError, Result},
+    game::{Card, CardCollection, GameInfo, GemArchtype},
     BidValue, CardIterator, PlayerInventory,
 };
 
-// TODO: (c)urrent-, (f)irst-, (h)ighest-markers
-
-/// TODO: docs
+/// A single-line description of a position, written as
+/// `bid/stack/inventories`.
+///
+/// The bid is the highest bid, or `-` if nobody has bid. The stack and each
+/// inventory list their non-leveraged cards, followed by `!` and their
+/// leveraged cards if there are any. Coin cards are written as their value
+/// and gem cards as their archtype code, such as `D` or `AE`. Inventories are
+/// separated by `;` and may start with the markers `c` for the current
+/// player, `f` for the first player of the current cycle, and `h` for the
+/// highest bidder, which is only marked once a bid has been made.
+///
+/// The round is not written, but derived from the number of cards dealt.
+/// Positions after the final reinvestment therefore read as the start of
+/// the final reinvestment.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GemNotation(String);
 
 impl Display for GemNotation {
@@ -82,36 +95,79 @@ impl Display for GemNotation {
     }
 }
 
+impl FromStr for GemNotation {
+    type Err = GemError;
+
+    /// Parses a notation, which is only accepted if it describes a valid
+    /// position.
+    fn from_str(s: &str) -> Result<Self> {
+        let notation = Self(s.trim().to_string());
+        notation.to_info()?;
+        Ok(notation)
+    }
+}
+
 impl GemNotation {
-    /// TODO: docs
+    /// Writes the position of the given [`GameInfo`].
     pub fn from_info(info: &GameInfo) -> Self {
         Self(Self::format(info))
     }
 
-    pub fn to_info(self) -> GameInfo {
-        todo!();
+    /// Reads the position into a [`GameInfo`]. This function will return an
+    /// error if the notation is invalid.
+    pub fn to_info(&self) -> Result<GameInfo> {
+        Self::parse(&self.0)
     }
 
-    /// TODO: docs
+    /// Returns the notation as a string.
     pub fn inner(self) -> String {
         self.0
     }
 }
 
-const ARCHTYPE_CODES: [&str; 16] = [
+/// The code of each [`GemArchtype`], indexed by the archtype index.
+pub const ARCHTYPE_CODES: [&str; 16] = [
     "D", "AA", "AE", "AS", "EE", "ER", "ET", "RA", "RR", "RT", "SE", "SR", "SS", "TA", "TS", "TT",
 ];
 
 impl GemNotation {
     pub fn format(info: &GameInfo) -> String {
+        let inventories = info
+            .inventories()
+            .iter()
+            .enumerate()
+            .map(|(idx, inv)| {
+                format!(
+                    "{}{}",
+                    Self::format_markers(info, idx),
+                    Self::format_cards(inv)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(";");
         format!(
             "{}/{}/{}",
             Self::format_highest_bid(info.highest_bid()),
             Self::format_cards(info.stack()),
-            Self::format_inventories(info.inventories()),
+            inventories,
         )
     }
 
+    /// Returns the markers of a single player, see [`GemNotation`].
+    pub fn format_markers(info: &GameInfo, idx: usize) -> String {
+        let mut markers = String::new();
+        if idx == info.current_player() {
+            markers.push('c');
+        }
+        if idx == info.starting_player() {
+            markers.push('f');
+        }
+        if idx == info.highest_bidder() && info.highest_bid() >= 0 {
+            markers.push('h');
+        }
+        markers
+    }
+
     pub fn format_highest_bid(bid: BidValue) -> String {
         if bid < 0 {
             '-'.to_string()
@@ -157,3 +213,201 @@ impl GemNotation {
         }
     }
 }
+
+//
+// Parsing
+//
+
+impl GemNotation {
+    fn parse(notation: &str) -> Result<GameInfo> {
+        let invalid = |reason: &str| GemError::InvalidNotation(format!("{reason} in `{notation}`"));
+
+        let parts = notation.trim().split('/').collect::<Vec<_>>();
+        let [bid, stack, inventories] = parts[..] else {
+            return Err(invalid("expected three parts separated by `/`"));
+        };
+
+        let highest_bid = match bid {
+            "-" => -1,
+            _ => bid
+                .parse::<BidValue>()
+                .ok()
+                .filter(|&bid| bid >= 0)
+                .ok_or_else(|| invalid("invalid highest bid"))?,
+        };
+
+        let mut stack_cards = CardCollection::<4>::default();
+        for card in Self::parse_cards(stack).ok_or_else(|| invalid("invalid stack"))? {
+            if card.is_coin() || stack_cards.len() == 4 {
+                return Err(invalid("invalid stack"));
+            }
+            stack_cards.push_back(card);
+        }
+
+        let inventories = inventories.split(';').collect::<Vec<_>>();
+        let num_players = inventories.len();
+        if !(2..=4).contains(&num_players) {
+            return Err(invalid("expected two to four inventories"));
+        }
+
+        let mut parsed: [PlayerInventory; 4] = Default::default();
+        let mut markers = [None; 3];
+        for (idx, inventory) in inventories.iter().enumerate() {
+            let cards = inventory.trim_start_matches(['c', 'f', 'h']);
+            for marker in inventory[..inventory.len() - cards.len()].chars() {
+                let slot = &mut markers["cfh".find(marker).unwrap()];
+                if slot.replace(idx).is_some() {
+                    return Err(invalid("duplicate marker"));
+                }
+            }
+
+            let cards = Self::parse_cards(cards).ok_or_else(|| invalid("invalid inventory"))?;
+            let mut coins = cards
+                .iter()
+                .coin_cards()
+                .map(|card| card.value())
+                .collect::<Vec<_>>();
+            coins.sort();
+            if coins != [1, 2, 3] || cards.len() > 21 {
+                return Err(invalid("invalid inventory"));
+            }
+            parsed[idx] = PlayerInventory::empty();
+            cards
+                .into_iter()
+                .for_each(|card| parsed[idx].push_back(card));
+        }
+
+        let current = markers[0].ok_or_else(|| invalid("missing current player"))?;
+        let starting = markers[1].unwrap_or(current);
+        let highest = match (highest_bid >= 0, markers[2]) {
+            (true, Some(highest)) => highest,
+            (true, None) => return Err(invalid("missing highest bidder")),
+            (false, Some(_)) => return Err(invalid("highest bidder without a bid")),
+            (false, None) => starting,
+        };
+
+        GameInfo::from_position(
+            num_players,
+            stack_cards,
+            parsed,
+            highest_bid,
+            [current, starting, highest],
+        )
+        .ok_or_else(|| invalid("impossible set of cards"))
+    }
+
+    /// Parses a list of cards, where every card after the `!` is leveraged.
+    fn parse_cards(cards: &str) -> Option<Vec<Card>> {
+        let (lhs, rhs) = cards.split_once('!').unwrap_or((cards, ""));
+        let mut parsed = Self::parse_card_codes(lhs)?;
+        if cards.contains('!') {
+            let leveraged = Self::parse_card_codes(rhs)?;
+            if leveraged.is_empty() {
+                return None;
+            }
+            parsed.extend(leveraged.into_iter().map(|card| card.with_leverage(true)));
+        }
+        Some(parsed)
+    }
+
+    fn parse_card_codes(codes: &str) -> Option<Vec<Card>> {
+        Self::split_codes(codes)?
+            .into_iter()
+            .map(Self::parse_card)
+            .collect()
+    }
+
+    /// Splits concatenated card codes, such as `12AE`, into single codes.
+    fn split_codes(mut codes: &str) -> Option<Vec<&str>> {
+        let mut split = Vec::new();
+        while !codes.is_empty() {
+            let len = match codes.starts_with(['1', '2', '3', 'D']) {
+                true => 1,
+                false => 2,
+            };
+            split.push(codes.get(..len)?);
+            codes = &codes[len..];
+        }
+        Some(split)
+    }
+
+    /// Returns the notation with the cards of the stack and of each inventory
+    /// sorted, and the leverage of the stack ignored, such that notations of
+    /// the same position compare equal.
+    pub(super) fn canonical(&self) -> String {
+        let sort = |codes: &str| {
+            let mut codes = Self::split_codes(codes).unwrap_or_default();
+            codes.sort_unstable();
+            codes.concat()
+        };
+        let sort_cards = |cards: &str| match cards.split_once('!') {
+            Some((lhs, rhs)) => format!("{}!{}", sort(lhs), sort(rhs)),
+            None => sort(cards),
+        };
+
+        let mut parts = self.0.splitn(3, '/');
+        let bid = parts.next().unwrap_or_default();
+        let stack = sort(&parts.next().unwrap_or_default().replace('!', ""));
+        let inventories = parts
+            .next()
+            .unwrap_or_default()
+            .split(';')
+            .map(|inventory| {
+                let cards = inventory.trim_start_matches(['c', 'f', 'h']);
+                let markers = &inventory[..inventory.len() - cards.len()];
+                format!("{markers}{}", sort_cards(cards))
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        format!("{bid}/{stack}/{inventories}")
+    }
+
+    /// Parses a single non-leveraged card written as a coin value, such as
+    /// `2`, or as a code of [`ARCHTYPE_CODES`], such as `SE`.
+    pub fn parse_card(code: &str) -> Option<Card> {
+        match code {
+            "1" | "2" | "3" => Some(Card::coin(code.parse().ok()?).with_leverage(false)),
+            _ => ARCHTYPE_CODES
+                .iter()
+                .position(|&other| other == code)
+                .map(|idx| Card::gem(GemArchtype::from_index(idx as u8)).with_leverage(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The positions of the example at the top of this file.
+    fn example() -> Vec<&'static str> {
+        let source = include_str!("notation.rs");
+        source[source.find("/*").unwrap() + 2..source.find("*/").unwrap()]
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("---"))
+            .collect()
+    }
+
+    #[test]
+    fn example_positions_round_trip() {
+        let positions = example();
+        assert!(!positions.is_empty());
+        for position in positions {
+            let info = position.parse::<GemNotation>().unwrap().to_info().unwrap();
+            assert_eq!(GemNotation::from_info(&info).to_string(), position);
+        }
+    }
+
+    #[test]
+    fn invalid_positions_are_rejected() {
+        for position in [
+            "",
+            "-/!AESED",
+            "x/!AESED/cf123;123;123",
+            "-/!AESED/cf123;123;1ZZ",
+        ] {
+            assert!(position.parse::<GemNotation>().is_err(), "{position}");
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    errors::{GemError, Result},
+    game::{Card, CardChoice, GameInfo},
+    CardIterator, Decision, Move,
+};
+
+use super::{narrator::Narrator, notation::GemNotation};
+
+/// A single line of a [`Transcript`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptLine {
+    /// A position, written as a [`GemNotation`].
+    Position(GemNotation),
+    /// A comment or annotation, written after `---`. Lines starting with `#`
+    /// are read as comments as well.
+    Comment(String),
+    /// An empty line, which separates unrelated sequences of positions.
+    Break,
+}
+
+/// An annotated sequence of positions, as in the example of [`GemNotation`].
+///
+/// Every line holds a position, a comment starting with `---` or `#`, or
+/// nothing. Consecutive positions are expected to follow from each other by a
+/// single step of the game, unless they are separated by an empty line.
+#[derive(Clone, Debug, Default)]
+pub struct Transcript {
+    lines: Vec<TranscriptLine>,
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lines(&self) -> &[TranscriptLine] {
+        &self.lines
+    }
+
+    /// Returns every position, in order.
+    pub fn positions(&self) -> impl Iterator<Item = &GemNotation> {
+        self.lines.iter().filter_map(|line| match line {
+            TranscriptLine::Position(notation) => Some(notation),
+            _ => None,
+        })
+    }
+
+    pub fn push_position(&mut self, info: &GameInfo) {
+        self.lines
+            .push(TranscriptLine::Position(GemNotation::from_info(info)));
+    }
+
+    /// Adds a comment, where every line of the comment becomes a separate
+    /// comment line.
+    pub fn push_comment(&mut self, comment: &str) {
+        for line in comment.lines() {
+            self.lines
+                .push(TranscriptLine::Comment(line.trim().to_string()));
+        }
+    }
+
+    pub fn push_break(&mut self) {
+        self.lines.push(TranscriptLine::Break);
+    }
+
+    /// Adds the narration of a step followed by the position after it, see
+    /// [`Narrator::narrate`].
+    pub fn push_step(&mut self, narrator: &Narrator, before: &GameInfo, after: &GameInfo) {
+        for sentence in narrator.narrate(before, after) {
+            self.push_comment(&sentence);
+        }
+        self.push_position(after);
+    }
+
+    /// Checks that every position follows from the previous position by a
+    /// single step, see [`Transcript::find_step`]. This function will return
+    /// an error naming the first position which does not.
+    pub fn validate(&self) -> Result<()> {
+        let mut previous: Option<(usize, &GemNotation)> = None;
+        for (idx, line) in self.lines.iter().enumerate() {
+            let notation = match line {
+                TranscriptLine::Position(notation) => notation,
+                TranscriptLine::Comment(_) => continue,
+                TranscriptLine::Break => {
+                    previous = None;
+                    continue;
+                }
+            };
+            if let Some((prev_idx, prev)) = previous {
+                if Self::find_step(&prev.to_info()?, notation).is_none() {
+                    return Err(GemError::InvalidTranscript(format!(
+                        "line {}: `{notation}` does not follow from `{prev}` on line {} by a \
+                         single step",
+                        idx + 1,
+                        prev_idx + 1
+                    )));
+                }
+            }
+            previous = Some((idx, notation));
+        }
+        Ok(())
+    }
+
+    /// Returns the moves leading from `info` to the position `to`, or `None`
+    /// if it does not follow by a single step. The moves are derived from the
+    /// difference between both positions: the card removed from the stack,
+    /// the cards used as payment and the flipped cards.
+    ///
+    /// A step is usually a single move, with two exceptions taken from the
+    /// example of [`GemNotation`]:
+    /// - the bid ending an auction may be shown together with the purchase of
+    ///   the highest bidder, returning both moves.
+    /// - the last reinvestment of a round may be shown before the coin cards
+    ///   are reset and new cards are drawn. The next position then follows by
+    ///   a reinvestment without flips.
+    ///
+    /// The cards drawn for a new round are not compared, as the order of the
+    /// deck is not part of the notation.
+    pub(super) fn find_step(info: &GameInfo, to: &GemNotation) -> Option<Vec<Move>> {
+        let target = to.to_info().ok()?;
+        let (player, decision) = info.next_decision()?;
+        match decision {
+            Decision::Bid => (0..=info.inventory_at(player).iter().capital()).find_map(|bid| {
+                if Self::leads_to(info, Move::Bid(bid), to) {
+                    return Some(vec![Move::Bid(bid)]);
+                }
+                let mut next = info.clone();
+                next.apply_bid(bid).ok()?;
+                let pick = Self::derive_pick(&next, &target)?;
+                Self::leads_to(&next, pick, to).then_some(vec![Move::Bid(bid), pick])
+            }),
+            Decision::PickCard => {
+                let pick = Self::derive_pick(info, &target)?;
+                Self::leads_to(info, pick, to).then_some(vec![pick])
+            }
+            Decision::Reinvest => {
+                let inventory = info.inventory_at(player).as_ref();
+                let (flipped, added) =
+                    Self::flipped(inventory, target.inventory_at(player).as_ref())?;
+                if !added.is_empty() {
+                    return None;
+                }
+                let flip = Move::Flip(CardChoice::new(&flipped));
+                if Self::leads_to(info, flip, to) {
+                    return Some(vec![flip]);
+                }
+                let mut next = info.clone();
+                if flip.apply(&mut next).is_ok() && next.round_index() > info.round_index() {
+                    let mut shown = info.clone();
+                    shown.flip_cards(player, CardChoice::new(&flipped));
+                    if GemNotation::from_info(&shown).canonical() == to.canonical() {
+                        return Some(vec![flip]);
+                    }
+                }
+
+                // the coin cards are reset when the round ends, so the coins
+                // flipped to pay for the gems are not part of the position
+                let mut flipped = flipped
+                    .into_iter()
+                    .filter(|&idx| !inventory[idx].is_coin())
+                    .collect::<Vec<_>>();
+                if info
+                    .inventory_at(player)
+                    .choose(CardChoice::new(&flipped))
+                    .scalar_value()
+                    < 0
+                {
+                    flipped.extend(
+                        (0..inventory.len()).filter(|&idx| {
+                            inventory[idx].is_coin() && !inventory[idx].is_leveraged()
+                        }),
+                    );
+                }
+                let flip = Move::Flip(CardChoice::new(&flipped));
+                Self::leads_to(info, flip, to).then_some(vec![flip])
+            }
+        }
+    }
+
+    /// Returns the purchase of the highest bidder in `info` which leaves them
+    /// with their inventory in `target`, if the next decision is a purchase.
+    fn derive_pick(info: &GameInfo, target: &GameInfo) -> Option<Move> {
+        let (player, Decision::PickCard) = info.next_decision()? else {
+            return None;
+        };
+        let (paid, added) = Self::flipped(
+            info.inventory_at(player).as_ref(),
+            target.inventory_at(player).as_ref(),
+        )?;
+        let [card] = added[..] else {
+            return None;
+        };
+        let slot = info
+            .stack()
+            .as_ref()
+            .iter()
+            .position(|&other| other == card)?;
+        Some(Move::Pick(slot, CardChoice::new(&paid)))
+    }
+
+    /// Matches the cards of an inventory to the cards of `target`, returning
+    /// the indices of the cards whose leverage differs together with the
+    /// cards of `target` which are left over. This function will return
+    /// `None` if a card is missing from `target`.
+    fn flipped(cards: &[Card], target: &[Card]) -> Option<(Vec<usize>, Vec<Card>)> {
+        let mut left = target.to_vec();
+        let mut take = |card: Card| {
+            let idx = left.iter().position(|&other| other == card)?;
+            Some(left.swap_remove(idx))
+        };
+        let mut flipped = Vec::new();
+        for (idx, &card) in cards.iter().enumerate() {
+            if take(card).is_none() {
+                take(card.with_leverage(!card.is_leveraged()))?;
+                flipped.push(idx);
+            }
+        }
+        Some((flipped, left))
+    }
+
+    /// Returns whether applying `step` to `info` leads to the position `to`.
+    fn leads_to(info: &GameInfo, step: Move, to: &GemNotation) -> bool {
+        let mut next = info.clone();
+        if step.apply(&mut next).is_err() {
+            return false;
+        }
+        let drawn = next.round_index() > info.round_index() && !next.game_over();
+        let key = |notation: &GemNotation| {
+            let canonical = notation.canonical();
+            match drawn {
+                true => canonical
+                    .splitn(3, '/')
+                    .enumerate()
+                    .filter(|&(idx, _)| idx != 1)
+                    .map(|(_, part)| part)
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                false => canonical,
+            }
+        };
+        key(&GemNotation::from_info(&next)) == key(to)
+    }
+}
+
+impl FromStr for Transcript {
+    type Err = GemError;
+
+    /// Reads a transcript, where every position must be a valid notation.
+    fn from_str(s: &str) -> Result<Self> {
+        let lines = s
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                let line = line.trim();
+                if line.is_empty() {
+                    return Ok(TranscriptLine::Break);
+                }
+                if let Some(comment) = line.strip_prefix("---").or(line.strip_prefix('#')) {
+                    return Ok(TranscriptLine::Comment(comment.trim().to_string()));
+                }
+                line.parse()
+                    .map(TranscriptLine::Position)
+                    .map_err(|err| GemError::InvalidTranscript(format!("line {}: {err}", idx + 1)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { lines })
+    }
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            match line {
+                TranscriptLine::Position(notation) => writeln!(f, "{notation}")?,
+                TranscriptLine::Comment(comment) => writeln!(f, "--- {comment}")?,
+                TranscriptLine::Break => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// The annotated example at the top of `notation.rs`.
+    pub(in crate::encoding) fn example() -> &'static str {
+        let source = include_str!("notation.rs");
+        &source[source.find("/*").unwrap() + 2..source.find("*/").unwrap()]
+    }
+
+    #[test]
+    fn notation_example_is_valid() {
+        let transcript = example().parse::<Transcript>().unwrap();
+        assert_eq!(transcript.positions().count(), 28);
+        transcript.validate().unwrap();
+    }
+
+    #[test]
+    fn positions_cannot_be_skipped() {
+        let example = example().replacen("3/!SED/12!3AE;fh123;c123\n", "", 1);
+        let err = example
+            .parse::<Transcript>()
+            .unwrap()
+            .validate()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`4/!SED/c12!3AE;f123;h123` does not follow"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn the_last_pass_may_be_shown_with_the_purchase() {
+        let from = "3/!AESED/fh123;123;c123".parse::<GemNotation>().unwrap();
+        let to = "-/!SED/12!3AE;cf123;123".parse::<GemNotation>().unwrap();
+        let step = Transcript::find_step(&from.to_info().unwrap(), &to).unwrap();
+        assert!(matches!(step[..], [Move::Bid(_), Move::Pick(0, _)]));
+    }
+
+    #[test]
+    fn hash_lines_are_comments() {
+        let transcript = "# recorded game\n-/!AESED/cf123;123;123\n"
+            .parse::<Transcript>()
+            .unwrap();
+        assert_eq!(
+            transcript.lines()[0],
+            TranscriptLine::Comment("recorded game".to_string())
+        );
+        assert_eq!(transcript.positions().count(), 1);
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    encoding::GemNotation,
+    errors::{GemError, Result},
+    game::{CardChoice, GameInfo},
+    player::PlayerBehavior,
+    BidValue, CardIterator, Move,
+};
+
+use super::protocol::{EngineCommand, EngineQuery, EngineReply};
+
+type FailureReport = Box<dyn FnMut(&GemError) + Send>;
+
+/// A running engine process, whose replies are read by a separate thread such
+/// that waiting for them can time out.
+struct EngineProcess {
+    child: Child,
+    input: ChildStdin,
+    replies: Receiver<String>,
+    last_info: Option<String>,
+}
+
+impl EngineProcess {
+    fn spawn(program: &str, args: &[String]) -> Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| GemError::EngineFailure(format!("cannot start `{program}`: {err}")))?;
+        let input = child.stdin.take().unwrap();
+        let output = child.stdout.take().unwrap();
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(output).lines().map_while(|line| line.ok()) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            input,
+            replies,
+            last_info: None,
+        })
+    }
+
+    fn send(&mut self, command: &EngineCommand) -> Result<()> {
+        writeln!(self.input, "{command}")
+            .and_then(|_| self.input.flush())
+            .map_err(|err| self.failure(&format!("cannot send `{command}`: {err}")))
+    }
+
+    /// Waits for the next reply accepted by `select`, ignoring every other
+    /// reply and any line which is not a reply.
+    fn receive<T>(
+        &mut self,
+        expected: &str,
+        deadline: Instant,
+        mut select: impl FnMut(EngineReply) -> Option<T>,
+    ) -> Result<T> {
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let line = match self.replies.recv_timeout(timeout) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(self.failure(&format!("timed out waiting for {expected}")))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(self.failure(&format!("exited while waiting for {expected}")))
+                }
+            };
+            match line.parse() {
+                Ok(EngineReply::Info(text)) => self.last_info = Some(text),
+                Ok(reply) => {
+                    if let Some(selected) = select(reply) {
+                        return Ok(selected);
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Describes a failure, including the last `info` sent by the engine.
+    fn failure(&self, reason: &str) -> GemError {
+        GemError::EngineFailure(match &self.last_info {
+            Some(info) => format!("engine {reason}, last info: {info}"),
+            None => format!("engine {reason}"),
+        })
+    }
+}
+
+impl Drop for EngineProcess {
+    /// Asks the engine to quit, and kills it if it does not exit in time.
+    fn drop(&mut self) {
+        let _ = self.send(&EngineCommand::Quit);
+        let deadline = Instant::now() + Duration::from_millis(100);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A [`PlayerBehavior`] played by an external engine process, which speaks the
+/// Gem Engine Interface over its standard input and output, see
+/// [`EngineCommand`].
+///
+/// An engine which exits or does not reply in time is restarted, and asked
+/// again. Once the restarts are used up, or the engine replies with an
+/// illegal move, the behavior resigns and [`EngineBehavior::failure`]
+/// describes why.
+pub struct EngineBehavior {
+    program: String,
+    args: Vec<String>,
+    options: Vec<(String, String)>,
+    timeout: Duration,
+    restarts: usize,
+    process: Option<EngineProcess>,
+    name: Option<String>,
+    failure: Option<GemError>,
+    report: Option<FailureReport>,
+}
+
+impl EngineBehavior {
+    /// Creates a behavior running the given program, with a timeout of five
+    /// seconds and a single restart.
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            options: Vec::new(),
+            timeout: Duration::from_secs(5),
+            restarts: 1,
+            process: None,
+            name: None,
+            failure: None,
+            report: None,
+        }
+    }
+
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets an option of the engine, which must be declared by the engine
+    /// during the handshake.
+    pub fn with_option(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the time the engine has for the handshake and for every decision.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how often the engine is restarted within a single decision.
+    pub fn with_restarts(mut self, restarts: usize) -> Self {
+        self.restarts = restarts;
+        self
+    }
+
+    /// Calls `report` once the engine fails and the behavior resigns, as the
+    /// [`Game`](crate::Game) only reports the resignation.
+    pub fn with_failure_report(mut self, report: impl FnMut(&GemError) + Send + 'static) -> Self {
+        self.report = Some(Box::new(report));
+        self
+    }
+
+    /// Starts the engine and completes the handshake, such that a
+    /// misconfigured engine is reported before the game starts.
+    pub fn start(mut self) -> Result<Self> {
+        self.process = Some(self.handshake()?);
+        Ok(self)
+    }
+
+    /// Returns the name the engine reported during the handshake.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns why the engine resigned, if it did.
+    pub fn failure(&self) -> Option<&GemError> {
+        self.failure.as_ref()
+    }
+
+    /// Spawns the engine, sets the options and waits until it is ready.
+    fn handshake(&mut self) -> Result<EngineProcess> {
+        let mut process = EngineProcess::spawn(&self.program, &self.args)?;
+        let deadline = Instant::now() + self.timeout;
+        process.send(&EngineCommand::Gei)?;
+        let mut declared = Vec::new();
+        process.receive("`geiok`", deadline, |reply| match reply {
+            EngineReply::Id { key, value } if key == "name" => {
+                self.name = Some(value);
+                None
+            }
+            EngineReply::Option { name, .. } => {
+                declared.push(name);
+                None
+            }
+            EngineReply::GeiOk => Some(()),
+            _ => None,
+        })?;
+
+        for (name, value) in &self.options {
+            if !declared.contains(name) {
+                return Err(GemError::EngineFailure(format!(
+                    "engine `{}` has no option `{name}`",
+                    self.program
+                )));
+            }
+            process.send(&EngineCommand::SetOption {
+                name: name.clone(),
+                value: value.clone(),
+            })?;
+        }
+        process.send(&EngineCommand::IsReady)?;
+        process.receive("`readyok`", deadline, |reply| {
+            matches!(reply, EngineReply::ReadyOk).then_some(())
+        })?;
+        process.send(&EngineCommand::NewGame)?;
+        Ok(process)
+    }
+
+    /// Asks the engine for a decision, restarting it whenever it fails to
+    /// reply. Returns the move if it is legal, and resigns otherwise.
+    fn decide(&mut self, info: &GameInfo, query: EngineQuery) -> Option<Move> {
+        if self.failure.is_some() {
+            return None;
+        }
+        let mut attempts = 0;
+        let reply = loop {
+            match self.ask(info, &query) {
+                Ok(reply) => break reply,
+                Err(err) => {
+                    self.process = None;
+                    attempts += 1;
+                    if attempts > self.restarts {
+                        self.fail(err);
+                        return None;
+                    }
+                }
+            }
+        };
+
+        let step = match (&query, &reply) {
+            (EngineQuery::Bid { .. }, &EngineReply::Bid(bid)) => Some(Move::Bid(bid)),
+            (
+                EngineQuery::Pick {
+                    stack, inventory, ..
+                },
+                EngineReply::Pick(slot, payment),
+            ) if *slot < stack.len() => {
+                Self::choice(payment, inventory.len()).map(|payment| Move::Pick(*slot, payment))
+            }
+            (EngineQuery::Reinvest { inventory }, EngineReply::Flip(flipped)) => {
+                Self::choice(flipped, inventory.len()).map(Move::Flip)
+            }
+            _ => None,
+        };
+        match step.filter(|&step| step.apply(&mut info.clone()).is_ok()) {
+            Some(step) => Some(step),
+            None => {
+                self.fail(GemError::EngineFailure(format!(
+                    "engine replied with illegal move `{reply}`"
+                )));
+                None
+            }
+        }
+    }
+
+    fn fail(&mut self, err: GemError) {
+        if let Some(report) = &mut self.report {
+            report(&err);
+        }
+        self.failure = Some(err);
+    }
+
+    fn ask(&mut self, info: &GameInfo, query: &EngineQuery) -> Result<EngineReply> {
+        if self.process.is_none() {
+            self.process = Some(self.handshake()?);
+        }
+        let process = self.process.as_mut().unwrap();
+        process.send(&EngineCommand::Position(GemNotation::from_info(info)))?;
+        process.send(&EngineCommand::Go {
+            query: query.clone(),
+            movetime: self.timeout.as_millis() as u64,
+        })?;
+        process.receive("a move", Instant::now() + self.timeout, |reply| {
+            matches!(
+                reply,
+                EngineReply::Bid(_) | EngineReply::Pick(..) | EngineReply::Flip(_)
+            )
+            .then_some(reply)
+        })
+    }
+
+    /// Converts inventory indices into a [`CardChoice`], rejecting indices
+    /// outside of the inventory.
+    fn choice(indices: &[usize], len: usize) -> Option<CardChoice> {
+        indices
+            .iter()
+            .all(|&idx| idx < len)
+            .then(|| CardChoice::new(indices))
+    }
+
+    fn player(info: &GameInfo) -> usize {
+        info.next_decision()
+            .map_or(info.current_player(), |(player, _)| player)
+    }
+}
+
+impl PlayerBehavior for EngineBehavior {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        let query = EngineQuery::Bid {
+            capital: info.inventory_at(Self::player(info)).iter().capital(),
+            highest: info.highest_bid(),
+        };
+        match self.decide(info, query) {
+            Some(Move::Bid(bid)) => bid,
+            _ => 0,
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        let query = EngineQuery::Pick {
+            price: info.highest_bid(),
+            stack: info.stack().as_ref().to_vec(),
+            inventory: info.inventory_at(Self::player(info)).as_ref().to_vec(),
+        };
+        match self.decide(info, query) {
+            Some(Move::Pick(slot, payment)) => (slot, payment),
+            _ => (0, CardChoice::NONE),
+        }
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        let query = EngineQuery::Reinvest {
+            inventory: info.inventory_at(Self::player(info)).as_ref().to_vec(),
+        };
+        match self.decide(info, query) {
+            Some(Move::Flip(flipped)) => flipped,
+            _ => CardChoice::NONE,
+        }
+    }
+
+    fn resigned(&self) -> bool {
+        self.failure.is_some()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Starts a shell script answering the handshake, which replies to every
+    /// `go` with `reply`.
+    fn engine(reply: &str) -> EngineBehavior {
+        let script = format!(
+            "while read command rest; do case $command in \
+             gei) echo 'id name script'; echo geiok;; \
+             isready) echo readyok;; \
+             go) echo '{reply}';; \
+             quit) exit;; \
+             esac; done"
+        );
+        EngineBehavior::new("sh")
+            .with_args(["-c", &script])
+            .with_timeout(Duration::from_secs(2))
+            .start()
+            .unwrap()
+    }
+
+    fn position(notation: &str) -> GameInfo {
+        notation.parse::<GemNotation>().unwrap().to_info().unwrap()
+    }
+
+    #[test]
+    fn legal_pick_is_played() {
+        let mut behavior = engine("pick 1 pay 0,2");
+        assert_eq!(behavior.name(), Some("script"));
+        let (slot, payment) = behavior.pick_card(&position("2/!AESEDRR/cfh123;123"));
+        assert_eq!(slot, 1);
+        assert!(payment.check(0) && !payment.check(1) && payment.check(2));
+        assert!(!behavior.resigned());
+    }
+
+    #[test]
+    fn pick_outside_of_the_stack_resigns() {
+        let mut behavior = engine("pick 4 pay 0,2");
+        behavior.pick_card(&position("2/!AESEDRR/cfh123;123"));
+        assert!(behavior.resigned());
+        assert_eq!(
+            behavior.failure().unwrap().to_string(),
+            "EngineFailure: engine replied with illegal move `pick 4 pay 0,2`"
+        );
+    }
+}
@@ -0,0 +1,7 @@
+mod adapter;
+mod protocol;
+mod server;
+
+pub use adapter::EngineBehavior;
+pub use protocol::{EngineCommand, EngineQuery, EngineReply};
+pub use server::{EngineFactory, EngineServer};
@@ -0,0 +1,354 @@
+//! The Gem Engine Interface, a line-based text protocol between a game and an
+//! engine process, modelled after the Universal Chess Interface.
+//!
+//! # Specification
+//!
+//! The game writes commands to the standard input of the engine, and the
+//! engine answers on its standard output. Every message is a single line of
+//! space-separated words. Messages which cannot be read are ignored by both
+//! sides, although an engine may report them with `info`.
+//!
+//! | command                           | reply                                 |
+//! |-----------------------------------|---------------------------------------|
+//! | `gei`                             | `id name <name>`, any number of       |
+//! |                                   | `option name <name> default <value>`, |
+//! |                                   | then `geiok`                          |
+//! | `setoption name <name> value <v>` | nothing                               |
+//! | `isready`                         | `readyok`                             |
+//! | `newgame`                         | nothing                               |
+//! | `position <notation>`             | nothing                               |
+//! | `go bid ...`                      | `bid <bid>`                           |
+//! | `go pick ...`                     | `pick <slot> pay <indices>`           |
+//! | `go reinvest ...`                 | `flip <indices>`                      |
+//! | `quit`                            | nothing, the engine exits             |
+//!
+//! The position is written as a [`GemNotation`], and `go` asks for the next
+//! decision of that position. It is followed by hints, which describe the
+//! legal moves such that an engine need not read the notation:
+//!
+//! - `go bid capital <capital> highest <bid>`, where any bid up to the
+//!   capital is legal and bids not above the highest bid pass. The highest
+//!   bid is `-` if nobody has bid.
+//! - `go pick price <price> stack <cards> inventory <cards>`, where the slot
+//!   indexes the stack and the payment indexes the inventory. Only
+//!   non-leveraged cards may be paid, and their scalar value must cover the
+//!   price.
+//! - `go reinvest inventory <cards>`, where the flipped cards index the
+//!   inventory, and the sum of their scalar values must not be negative.
+//!
+//! Every hint ends with `movetime <milliseconds>`, the time the engine has
+//! to reply. Cards are listed as codes of [`GemNotation`] separated by `,`,
+//! with `!` before leveraged cards, such as `1,!2,AE`. Indices are separated
+//! by `,` as well, and an empty list is written as `-`. An engine may send
+//! `info <text>` at any time, which the game ignores.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    encoding::GemNotation,
+    errors::{GemError, Result},
+    BidValue, Card,
+};
+
+/// A command sent to an engine, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineCommand {
+    Gei,
+    SetOption { name: String, value: String },
+    IsReady,
+    NewGame,
+    Position(GemNotation),
+    Go { query: EngineQuery, movetime: u64 },
+    Quit,
+}
+
+/// The decision an engine is asked to make, with hints about the legal moves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineQuery {
+    Bid {
+        capital: BidValue,
+        highest: BidValue,
+    },
+    Pick {
+        price: BidValue,
+        stack: Vec<Card>,
+        inventory: Vec<Card>,
+    },
+    Reinvest {
+        inventory: Vec<Card>,
+    },
+}
+
+/// A reply sent by an engine, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineReply {
+    Id {
+        key: String,
+        value: String,
+    },
+    Option {
+        name: String,
+        default: String,
+    },
+    GeiOk,
+    ReadyOk,
+    Info(String),
+    Bid(BidValue),
+    /// The slot of the stack and the inventory indices of the payment.
+    Pick(usize, Vec<usize>),
+    /// The inventory indices of the flipped cards.
+    Flip(Vec<usize>),
+}
+
+impl Display for EngineCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gei => write!(f, "gei"),
+            Self::SetOption { name, value } => write!(f, "setoption name {name} value {value}"),
+            Self::IsReady => write!(f, "isready"),
+            Self::NewGame => write!(f, "newgame"),
+            Self::Position(notation) => write!(f, "position {notation}"),
+            Self::Go { query, movetime } => match query {
+                EngineQuery::Bid { capital, highest } => write!(
+                    f,
+                    "go bid capital {capital} highest {} movetime {movetime}",
+                    GemNotation::format_highest_bid(*highest)
+                ),
+                EngineQuery::Pick {
+                    price,
+                    stack,
+                    inventory,
+                } => write!(
+                    f,
+                    "go pick price {price} stack {} inventory {} movetime {movetime}",
+                    format_card_list(stack),
+                    format_card_list(inventory)
+                ),
+                EngineQuery::Reinvest { inventory } => write!(
+                    f,
+                    "go reinvest inventory {} movetime {movetime}",
+                    format_card_list(inventory)
+                ),
+            },
+            Self::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for EngineCommand {
+    type Err = GemError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || GemError::InvalidEngineMessage(s.trim().to_string());
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        Ok(match words[..] {
+            ["gei"] => Self::Gei,
+            ["setoption", "name", name, "value", ref value @ ..] => Self::SetOption {
+                name: name.to_string(),
+                value: value.join(" "),
+            },
+            ["isready"] => Self::IsReady,
+            ["newgame"] => Self::NewGame,
+            ["position", notation] => Self::Position(notation.parse()?),
+            ["go", decision, ref hints @ ..] => {
+                let hint = |key: &str| {
+                    hints
+                        .iter()
+                        .position(|&word| word == key)
+                        .and_then(|idx| hints.get(idx + 1))
+                        .copied()
+                        .ok_or_else(invalid)
+                };
+                let number = |key: &str| {
+                    let value = hint(key)?;
+                    match value {
+                        "-" => Ok(-1),
+                        _ => value.parse::<BidValue>().map_err(|_| invalid()),
+                    }
+                };
+                let cards = |key: &str| parse_card_list(hint(key)?).ok_or_else(invalid);
+                let query = match decision {
+                    "bid" => EngineQuery::Bid {
+                        capital: number("capital")?,
+                        highest: number("highest")?,
+                    },
+                    "pick" => EngineQuery::Pick {
+                        price: number("price")?,
+                        stack: cards("stack")?,
+                        inventory: cards("inventory")?,
+                    },
+                    "reinvest" => EngineQuery::Reinvest {
+                        inventory: cards("inventory")?,
+                    },
+                    _ => return Err(invalid()),
+                };
+                let movetime = hint("movetime")?.parse().map_err(|_| invalid())?;
+                Self::Go { query, movetime }
+            }
+            ["quit"] => Self::Quit,
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+impl Display for EngineReply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id { key, value } => write!(f, "id {key} {value}"),
+            Self::Option { name, default } => write!(f, "option name {name} default {default}"),
+            Self::GeiOk => write!(f, "geiok"),
+            Self::ReadyOk => write!(f, "readyok"),
+            Self::Info(text) => write!(f, "info {text}"),
+            Self::Bid(bid) => write!(f, "bid {bid}"),
+            Self::Pick(slot, payment) => write!(f, "pick {slot} pay {}", format_indices(payment)),
+            Self::Flip(flipped) => write!(f, "flip {}", format_indices(flipped)),
+        }
+    }
+}
+
+impl FromStr for EngineReply {
+    type Err = GemError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || GemError::InvalidEngineMessage(s.trim().to_string());
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        Ok(match words[..] {
+            ["id", key, ref value @ ..] => Self::Id {
+                key: key.to_string(),
+                value: value.join(" "),
+            },
+            ["option", "name", name, "default", ref default @ ..] => Self::Option {
+                name: name.to_string(),
+                default: default.join(" "),
+            },
+            ["geiok"] => Self::GeiOk,
+            ["readyok"] => Self::ReadyOk,
+            ["info", ref text @ ..] => Self::Info(text.join(" ")),
+            ["bid", bid] => Self::Bid(bid.parse().map_err(|_| invalid())?),
+            ["pick", slot, "pay", payment] => Self::Pick(
+                slot.parse().map_err(|_| invalid())?,
+                parse_indices(payment).ok_or_else(invalid)?,
+            ),
+            ["flip", flipped] => Self::Flip(parse_indices(flipped).ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+fn format_card_list(cards: &[Card]) -> String {
+    if cards.is_empty() {
+        return "-".to_string();
+    }
+    cards
+        .iter()
+        .map(|&card| match card.is_leveraged() {
+            true => format!("!{}", GemNotation::format_card(card)),
+            false => GemNotation::format_card(card),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_card_list(cards: &str) -> Option<Vec<Card>> {
+    if cards == "-" {
+        return Some(Vec::new());
+    }
+    cards
+        .split(',')
+        .map(|code| match code.strip_prefix('!') {
+            Some(code) => GemNotation::parse_card(code).map(|card| card.with_leverage(true)),
+            None => GemNotation::parse_card(code),
+        })
+        .collect()
+}
+
+fn format_indices(indices: &[usize]) -> String {
+    match indices.is_empty() {
+        true => "-".to_string(),
+        false => indices
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+    }
+}
+
+fn parse_indices(indices: &str) -> Option<Vec<usize>> {
+    match indices {
+        "-" => Some(Vec::new()),
+        _ => indices.split(',').map(|idx| idx.parse().ok()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(codes: &str) -> Vec<Card> {
+        parse_card_list(codes).unwrap()
+    }
+
+    #[test]
+    fn commands_round_trip() {
+        let commands = [
+            "gei",
+            "setoption name bid_rate value 0.7",
+            "isready",
+            "newgame",
+            "position 3/!AESED/fh123;c123;123",
+            "go bid capital 6 highest - movetime 100",
+            "go bid capital 6 highest 3 movetime 100",
+            "go pick price 4 stack !SE,D inventory 1,2,!3,AE movetime 5000",
+            "go reinvest inventory - movetime 100",
+            "quit",
+        ];
+        for line in commands {
+            let command = line.parse::<EngineCommand>().unwrap();
+            assert_eq!(command.to_string(), line);
+        }
+        assert_eq!(
+            commands[7].parse::<EngineCommand>().unwrap(),
+            EngineCommand::Go {
+                query: EngineQuery::Pick {
+                    price: 4,
+                    stack: cards("!SE,D"),
+                    inventory: cards("1,2,!3,AE"),
+                },
+                movetime: 5000,
+            }
+        );
+    }
+
+    #[test]
+    fn replies_round_trip() {
+        let replies = [
+            "id name greedy",
+            "option name bid_rate default 0.5",
+            "geiok",
+            "readyok",
+            "info depth 3 score 12",
+            "bid 0",
+            "pick 1 pay 0,2",
+            "pick 0 pay -",
+            "flip 3",
+        ];
+        for line in replies {
+            let reply = line.parse::<EngineReply>().unwrap();
+            assert_eq!(reply.to_string(), line);
+        }
+        assert_eq!(
+            replies[6].parse::<EngineReply>().unwrap(),
+            EngineReply::Pick(1, vec![0, 2])
+        );
+    }
+
+    #[test]
+    fn malformed_messages_are_rejected() {
+        for line in ["", "go", "go bid capital 6", "position", "go pick price x"] {
+            assert!(line.parse::<EngineCommand>().is_err(), "{line}");
+        }
+        for line in ["bid", "bid x", "pick 1", "pick 1 pay 0,x", "flip"] {
+            assert!(line.parse::<EngineReply>().is_err(), "{line}");
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    errors::Result,
+    game::{Card, CardChoice, Decision, GameInfo},
+    player::SendBehavior,
+    PlayerBehavior,
+};
+
+use super::protocol::{EngineCommand, EngineQuery, EngineReply};
+
+/// A function creating a behavior from the current options of an
+/// [`EngineServer`].
+pub type EngineFactory = Box<dyn FnMut(&[(String, String)]) -> Result<Box<SendBehavior>>>;
+
+/// Serves a [`PlayerBehavior`] over the Gem Engine Interface, such that it can
+/// be played by an [`EngineBehavior`](super::EngineBehavior) in another
+/// process.
+///
+/// The behavior is created from the current options, and created again for
+/// every new game or once the options change. Errors are reported to the
+/// game as `info error ...`.
+pub struct EngineServer {
+    name: String,
+    options: Vec<(String, String)>,
+    factory: EngineFactory,
+    behavior: Option<Box<SendBehavior>>,
+    position: Option<GameInfo>,
+}
+
+impl EngineServer {
+    pub fn new(
+        name: impl Into<String>,
+        factory: impl FnMut(&[(String, String)]) -> Result<Box<SendBehavior>> + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            options: Vec::new(),
+            factory: Box::new(factory),
+            behavior: None,
+            position: None,
+        }
+    }
+
+    /// Declares an option with its default value, which is announced during
+    /// the handshake. Options which were not declared can still be set.
+    pub fn with_option(mut self, name: impl Into<String>, default: impl Into<String>) -> Self {
+        self.options.push((name.into(), default.into()));
+        self
+    }
+
+    /// Answers commands until `quit` is received or the input ends. This
+    /// function will only return an error if reading or writing fails.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let command = match line.parse::<EngineCommand>() {
+                Ok(command) => command,
+                Err(err) => {
+                    if line.split_whitespace().next() == Some("position") {
+                        self.position = None;
+                    }
+                    Self::reply(&mut output, EngineReply::Info(format!("error {err}")))?;
+                    continue;
+                }
+            };
+            match command {
+                EngineCommand::Gei => {
+                    Self::reply(
+                        &mut output,
+                        EngineReply::Id {
+                            key: "name".to_string(),
+                            value: self.name.clone(),
+                        },
+                    )?;
+                    for (name, default) in &self.options {
+                        Self::reply(
+                            &mut output,
+                            EngineReply::Option {
+                                name: name.clone(),
+                                default: default.clone(),
+                            },
+                        )?;
+                    }
+                    Self::reply(&mut output, EngineReply::GeiOk)?;
+                }
+                EngineCommand::SetOption { name, value } => {
+                    match self.options.iter_mut().find(|(other, _)| *other == name) {
+                        Some((_, current)) => *current = value,
+                        None => self.options.push((name, value)),
+                    }
+                    self.behavior = None;
+                }
+                EngineCommand::IsReady => {
+                    if let Err(err) = self.behavior() {
+                        Self::reply(&mut output, EngineReply::Info(format!("error {err}")))?;
+                    }
+                    Self::reply(&mut output, EngineReply::ReadyOk)?;
+                }
+                EngineCommand::NewGame => self.behavior = None,
+                // the notation was checked while parsing the command
+                EngineCommand::Position(notation) => self.position = notation.to_info().ok(),
+                EngineCommand::Go { query, .. } => {
+                    let reply = match self.go(&query) {
+                        Ok(reply) => reply,
+                        Err(err) => EngineReply::Info(format!("error {err}")),
+                    };
+                    Self::reply(&mut output, reply)?;
+                }
+                EngineCommand::Quit => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn behavior(&mut self) -> Result<&mut Box<SendBehavior>> {
+        if self.behavior.is_none() {
+            self.behavior = Some((self.factory)(&self.options)?);
+        }
+        Ok(self.behavior.as_mut().unwrap())
+    }
+
+    /// Asks the behavior for the decision of the last position.
+    fn go(&mut self, query: &EngineQuery) -> std::result::Result<EngineReply, String> {
+        let mut info = self.position.clone().ok_or("no position was given")?;
+        let (player, decision) = info.next_decision().ok_or("the game is over")?;
+        let asked = match query {
+            EngineQuery::Bid { .. } => Decision::Bid,
+            EngineQuery::Pick { .. } => Decision::PickCard,
+            EngineQuery::Reinvest { .. } => Decision::Reinvest,
+        };
+        if asked != decision {
+            return Err(format!(
+                "expected {decision:?} for the position, not {asked:?}"
+            ));
+        }
+        info.set_current_player(player);
+        let cards = info.inventory_at(player).as_ref().to_vec();
+
+        let behavior = self.behavior().map_err(|err| err.to_string())?;
+        Ok(match query {
+            EngineQuery::Bid { .. } => EngineReply::Bid(behavior.bid(&info)),
+            EngineQuery::Pick { inventory, .. } => {
+                let (slot, payment) = behavior.pick_card(&info);
+                EngineReply::Pick(slot, Self::indices(payment, &cards, inventory)?)
+            }
+            EngineQuery::Reinvest { inventory } => {
+                let flipped = behavior.reinvest(&info);
+                EngineReply::Flip(Self::indices(flipped, &cards, inventory)?)
+            }
+        })
+    }
+
+    /// Converts a choice of the cards of the position into indices of the
+    /// inventory given by the hints, whose order may differ from the order of
+    /// the notation.
+    fn indices(
+        choice: CardChoice,
+        cards: &[Card],
+        inventory: &[Card],
+    ) -> std::result::Result<Vec<usize>, String> {
+        let mut used = vec![false; inventory.len()];
+        let mut indices = (0..cards.len())
+            .filter(|&idx| choice.check(idx))
+            .map(|idx| {
+                let found = (0..inventory.len())
+                    .find(|&other| !used[other] && inventory[other] == cards[idx])
+                    .ok_or("the inventory does not match the position")?;
+                used[found] = true;
+                Ok(found)
+            })
+            .collect::<std::result::Result<Vec<_>, String>>()?;
+        indices.sort_unstable();
+        Ok(indices)
+    }
+
+    fn reply(output: &mut impl Write, reply: EngineReply) -> io::Result<()> {
+        writeln!(output, "{reply}")?;
+        output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{errors::GemError, BidValue};
+
+    /// Bids the value of its `bid` option, and picks the second card of the
+    /// stack paying with the first and the last card of the inventory.
+    struct Scripted {
+        bid: BidValue,
+    }
+
+    impl PlayerBehavior for Scripted {
+        fn bid(&mut self, _info: &GameInfo) -> BidValue {
+            self.bid
+        }
+
+        fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+            let last = info.my_inventory().len() - 1;
+            (1, CardChoice::new(&[0, last]))
+        }
+
+        fn reinvest(&mut self, _info: &GameInfo) -> CardChoice {
+            CardChoice::NONE
+        }
+    }
+
+    fn run(input: &str) -> Vec<String> {
+        let mut server = EngineServer::new("scripted", |options: &[(String, String)]| {
+            let (_, bid) = options.iter().find(|(name, _)| name == "bid").unwrap();
+            let bid = bid
+                .parse()
+                .map_err(|_| GemError::InvalidEngineMessage(bid.clone()))?;
+            Ok(Box::new(Scripted { bid }) as Box<SendBehavior>)
+        })
+        .with_option("bid", "0");
+        let mut output = Vec::new();
+        server.run(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn answers_a_scripted_game() {
+        let output = run("gei\n\
+             setoption name bid value 3\n\
+             isready\n\
+             newgame\n\
+             position -/!AESED/cf123;123;123\n\
+             go bid capital 6 highest - movetime 100\n\
+             position 2/!AESEDRR/cfh123;123\n\
+             go pick price 2 stack !AE,SE,D,RR inventory 2,3,1 movetime 100\n\
+             go bid capital 6 highest 2 movetime 100\n\
+             quit\n\
+             isready\n");
+        assert_eq!(
+            output,
+            [
+                "id name scripted",
+                "option name bid default 0",
+                "geiok",
+                "readyok",
+                "bid 3",
+                // coins 1 and 3 of the position are the last two of the hints
+                "pick 1 pay 1,2",
+                "info error expected PickCard for the position, not Bid",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_errors_and_forgets_bad_positions() {
+        let output = run("position -/!AESED/cf123;123;123\n\
+             position -/!AESED/cf123\n\
+             go bid capital 6 highest - movetime 100\n\
+             setoption name bid value x\n\
+             isready\n");
+        assert_eq!(output.len(), 4);
+        assert!(output[0].starts_with("info error "));
+        assert_eq!(output[1], "info error no position was given");
+        assert_eq!(output[2], "info error InvalidEngineMessage: `x`");
+        assert_eq!(output[3], "readyok");
+    }
+}
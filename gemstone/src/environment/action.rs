@@ -0,0 +1,245 @@
+use crate::{
+    errors::Result, BidValue, CardChoice, CardIterator, Decision, GameInfo, KeepCoins,
+    MinimalOverpay, Payment, PaymentOptimizer, ProtectMajorities,
+};
+
+/// The number of bid actions, covering bids in range `[0..NUM_BIDS)`.
+pub const NUM_BIDS: usize = 32;
+/// The number of stack slots a card can be picked from.
+pub const NUM_STACK_SLOTS: usize = 4;
+/// The number of leveraged gem cards which can be chosen in a flip action.
+pub const NUM_FLIP_SLOTS: usize = 4;
+/// The total size of the action space.
+pub const ACTION_COUNT: usize =
+    NUM_BIDS + NUM_STACK_SLOTS * PaymentModel::COUNT + (1 << NUM_FLIP_SLOTS);
+
+/// The cost model used to pay for a card picked by a [`Action::Pick`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaymentModel {
+    /// Pay using [`MinimalOverpay`].
+    MinimalOverpay = 0,
+    /// Pay using [`KeepCoins`].
+    KeepCoins,
+    /// Pay using [`ProtectMajorities::from_info`].
+    ProtectMajorities,
+}
+
+impl PaymentModel {
+    pub const COUNT: usize = 3;
+
+    pub fn from_index(idx: usize) -> Self {
+        match idx {
+            0 => Self::MinimalOverpay,
+            1 => Self::KeepCoins,
+            2 => Self::ProtectMajorities,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the best payment of `price` for a player under this model.
+    pub fn pay(self, info: &GameInfo, player: usize, price: BidValue) -> Option<Payment> {
+        let optimizer = PaymentOptimizer::new(info.inventory_at(player));
+        match self {
+            Self::MinimalOverpay => optimizer.best(price, &MinimalOverpay),
+            Self::KeepCoins => optimizer.best(price, &KeepCoins),
+            Self::ProtectMajorities => {
+                optimizer.best(price, &ProtectMajorities::from_info(info, player))
+            }
+        }
+    }
+}
+
+/// A single action of the fixed action space used by the
+/// [`GemEnv`](super::GemEnv). Actions are enumerated in the order bids,
+/// picks and flips:
+///
+/// - `[0..32)`: bid the index, where bids not exceeding the highest bid pass.
+/// - `[32..44)`: pick the card at stack slot `(idx - 32) / 3` and pay with the
+///   [`PaymentModel`] at `(idx - 32) % 3`.
+/// - `[44..60)`: flip a subset of the first four leveraged gem cards in the
+///   inventory, where bit `i` of `idx - 44` selects the `i`th such card. The
+///   flip is paid for using [`ProtectMajorities::from_info`].
+///
+/// The action space is smaller than the set of legal moves. Bids of 32 or
+/// more, payments other than the best payment of each [`PaymentModel`],
+/// flips including any leveraged gem card after the fourth, and flips paid
+/// for in any other way cannot be expressed. These moves are never legal
+/// actions, but the [`fallback`](Self::fallback) of every decision always is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Bid(BidValue),
+    Pick { slot: usize, payment: PaymentModel },
+    Flip { subset: u8 },
+}
+
+impl Action {
+    /// Returns the action with the given index, or `None` if the index is
+    /// outside the action space.
+    pub fn from_index(idx: usize) -> Option<Self> {
+        let picks = NUM_STACK_SLOTS * PaymentModel::COUNT;
+        Some(match idx {
+            _ if idx < NUM_BIDS => Self::Bid(idx as BidValue),
+            _ if idx < NUM_BIDS + picks => Self::Pick {
+                slot: (idx - NUM_BIDS) / PaymentModel::COUNT,
+                payment: PaymentModel::from_index((idx - NUM_BIDS) % PaymentModel::COUNT),
+            },
+            _ if idx < ACTION_COUNT => Self::Flip {
+                subset: (idx - NUM_BIDS - picks) as u8,
+            },
+            _ => return None,
+        })
+    }
+
+    /// Returns the index of this action in range `[0..ACTION_COUNT)`.
+    pub fn index(self) -> usize {
+        match self {
+            Self::Bid(bid) => bid as usize,
+            Self::Pick { slot, payment } => {
+                NUM_BIDS + slot * PaymentModel::COUNT + payment as usize
+            }
+            Self::Flip { subset } => {
+                NUM_BIDS + NUM_STACK_SLOTS * PaymentModel::COUNT + subset as usize
+            }
+        }
+    }
+
+    /// Returns the kind of decision this action belongs to.
+    pub fn decision(self) -> Decision {
+        match self {
+            Self::Bid(_) => Decision::Bid,
+            Self::Pick { .. } => Decision::PickCard,
+            Self::Flip { .. } => Decision::Reinvest,
+        }
+    }
+
+    /// Returns the action which is legal in every state with the given
+    /// decision, namely passing, picking the first card of the stack using
+    /// [`MinimalOverpay`] and flipping nothing.
+    pub fn fallback(decision: Decision) -> Self {
+        match decision {
+            Decision::Bid => Self::Bid(0),
+            Decision::PickCard => Self::Pick {
+                slot: 0,
+                payment: PaymentModel::MinimalOverpay,
+            },
+            Decision::Reinvest => Self::Flip { subset: 0 },
+        }
+    }
+
+    /// Translates this action into the move of the player making the next
+    /// decision, or returns `None` if the action is illegal in this state.
+    pub fn resolve(self, info: &GameInfo) -> Option<Move> {
+        let (player, decision) = info.next_decision()?;
+        if decision != self.decision() {
+            return None;
+        }
+        let inventory = info.inventory_at(player);
+
+        match self {
+            Self::Bid(bid) => match bid <= inventory.iter().capital() {
+                true => Some(Move::Bid(bid)),
+                false => None,
+            },
+            Self::Pick { slot, payment } => {
+                if slot >= info.stack_size() {
+                    return None;
+                }
+                let payment = payment.pay(info, player, info.highest_bid().max(0))?;
+                Some(Move::Pick(slot, payment.choice))
+            }
+            Self::Flip { subset } => {
+                let targets = inventory
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, card)| card.is_leveraged() && !card.is_coin())
+                    .map(|(i, _)| i)
+                    .take(NUM_FLIP_SLOTS)
+                    .collect::<Vec<_>>();
+                if subset >> targets.len() != 0 {
+                    return None;
+                }
+                let chosen = (0..targets.len())
+                    .filter(|&bit| subset & (1 << bit) != 0)
+                    .map(|bit| targets[bit])
+                    .collect::<Vec<_>>();
+                // un-leveraging a card costs its value minus one
+                let price = chosen
+                    .iter()
+                    .map(|&i| -inventory.as_ref()[i].scalar_value())
+                    .sum::<BidValue>();
+                let payment = PaymentModel::ProtectMajorities.pay(info, player, price)?;
+                let paid = (0..inventory.len()).filter(|&i| payment.choice.check(i));
+                let indices = chosen.iter().cloned().chain(paid).collect::<Vec<_>>();
+                Some(Move::Flip(CardChoice::new(&indices)))
+            }
+        }
+    }
+
+    /// Returns every legal action of the next decision together with its
+    /// resolved move.
+    pub fn legal_moves(info: &GameInfo) -> Vec<(Action, Move)> {
+        (0..ACTION_COUNT)
+            .map(|idx| Self::from_index(idx).unwrap())
+            .filter_map(|action| action.resolve(info).map(|next| (action, next)))
+            .collect()
+    }
+
+    /// Returns the legal-action mask of the next decision, which is empty
+    /// of legal actions if the game has ended.
+    pub fn legal_mask(info: &GameInfo) -> Vec<bool> {
+        (0..ACTION_COUNT)
+            .map(|idx| Self::from_index(idx).unwrap().resolve(info).is_some())
+            .collect()
+    }
+}
+
+/// A concrete move, as returned by the [`PlayerBehavior`](crate::PlayerBehavior)
+/// function of the corresponding decision.
+#[derive(Clone, Copy, Debug)]
+pub enum Move {
+    Bid(BidValue),
+    Pick(usize, CardChoice),
+    Flip(CardChoice),
+}
+
+impl Move {
+    /// Applies this move for the player making the next decision.
+    pub fn apply(self, info: &mut GameInfo) -> Result<()> {
+        match self {
+            Self::Bid(bid) => info.apply_bid(bid),
+            Self::Pick(slot, payment) => info.apply_pick(slot, payment),
+            Self::Flip(choice) => info.apply_reinvest(choice),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn legal_actions_match_the_mask_and_apply() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for num_players in 2..=4 {
+            let mut info = GameInfo::with_rng(num_players, &mut rng);
+            info.prepare_auction();
+            while let Some((_, decision)) = info.next_decision() {
+                let mask = Action::legal_mask(&info);
+                let moves = Action::legal_moves(&info);
+                assert_eq!(mask.iter().filter(|&&legal| legal).count(), moves.len());
+                assert!(mask[Action::fallback(decision).index()]);
+
+                for &(action, next) in &moves {
+                    assert!(mask[action.index()]);
+                    assert_eq!(action.decision(), decision);
+                    assert_eq!(Action::from_index(action.index()), Some(action));
+                    assert!(next.apply(&mut info.clone()).is_ok(), "{action:?}");
+                }
+                let &(_, next) = moves.choose(&mut rng).unwrap();
+                next.apply(&mut info).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    encoding::FeatureEncoder,
+    errors::{GemError, Result},
+    game::{CardChoice, Decision, Game, GameInfo, GameScores},
+    player::PlayerBehavior,
+    tournament::BehaviorFactory,
+    BidValue,
+};
+
+use super::{Action, Move};
+
+/// The view of the player making the next decision.
+#[derive(Clone, Debug)]
+pub struct Observation {
+    /// The seat of the observing player.
+    pub player: usize,
+    /// The decision to be made, or `None` if the game has ended.
+    pub decision: Option<Decision>,
+    /// The features of the game from the perspective of the observing player,
+    /// see [`FeatureEncoder`].
+    pub features: Vec<f32>,
+    /// Whether each action of the action space is legal, indexed by the
+    /// action index.
+    pub legal: Vec<bool>,
+}
+
+/// Additional information returned by [`GemEnv::step`].
+#[derive(Clone, Debug)]
+pub struct StepInfo {
+    pub round_index: usize,
+    /// The number of decisions made by opponents during the step.
+    pub opponent_decisions: usize,
+    /// The final scores, once the game has ended.
+    pub scores: Option<GameScores>,
+}
+
+/// The result of [`GemEnv::step`].
+#[derive(Clone, Debug)]
+pub struct StepResult {
+    pub observation: Observation,
+    /// The reward of each seat, which is zero until the game has ended. The
+    /// final reward is the score of the seat minus the average score of the
+    /// other seats.
+    pub rewards: Vec<f32>,
+    pub done: bool,
+    pub info: StepInfo,
+}
+
+/// Takes the decisions of an agent-controlled seat from the move stored by
+/// the environment.
+struct AgentSeat {
+    next: Rc<RefCell<Option<Move>>>,
+}
+
+impl AgentSeat {
+    fn take(&self) -> Move {
+        self.next
+            .borrow_mut()
+            .take()
+            .expect("agent seat asked for a decision without an action")
+    }
+}
+
+impl PlayerBehavior for AgentSeat {
+    fn bid(&mut self, _info: &GameInfo) -> BidValue {
+        match self.take() {
+            Move::Bid(bid) => bid,
+            other => unreachable!("expected a bid, got {other:?}"),
+        }
+    }
+
+    fn pick_card(&mut self, _info: &GameInfo) -> (usize, CardChoice) {
+        match self.take() {
+            Move::Pick(slot, payment) => (slot, payment),
+            other => unreachable!("expected a pick, got {other:?}"),
+        }
+    }
+
+    fn reinvest(&mut self, _info: &GameInfo) -> CardChoice {
+        match self.take() {
+            Move::Flip(choice) => choice,
+            other => unreachable!("expected a flip, got {other:?}"),
+        }
+    }
+}
+
+/// A reinforcement learning environment with a fixed action space, see
+/// [`Action`] for how actions are enumerated.
+///
+/// Each seat is either controlled by the agent or by an opponent
+/// [`PlayerBehavior`]. The environment plays the decisions of opponents
+/// automatically, such that every observation belongs to an agent seat.
+/// Adding several agent seats allows training through self-play.
+pub struct GemEnv {
+    seats: Vec<Option<BehaviorFactory>>,
+    game: Option<Game>,
+    next: Rc<RefCell<Option<Move>>>,
+}
+
+impl Default for GemEnv {
+    fn default() -> Self {
+        Self {
+            seats: Vec::new(),
+            game: None,
+            next: Rc::new(RefCell::new(None)),
+        }
+    }
+}
+
+impl GemEnv {
+    /// Adds a seat controlled by the agent.
+    pub fn add_agent(&mut self) {
+        self.seats.push(None);
+    }
+
+    /// Adds a seat controlled by an opponent, where `factory` creates a new
+    /// behavior for every game.
+    pub fn add_opponent(&mut self, factory: impl Fn() -> Box<dyn PlayerBehavior> + 'static) {
+        self.seats.push(Some(Box::new(factory)));
+    }
+
+    /// Returns the [`GameInfo`] of the current game, if any.
+    pub fn info(&self) -> Option<&GameInfo> {
+        self.game.as_ref().map(Game::info_ref)
+    }
+
+    /// Starts a new game dealt from the given seed and plays until the first
+    /// decision of an agent seat. This function will return an error if the
+    /// number of seats is invalid, if there is no agent seat, or if an
+    /// opponent makes an illegal move.
+    pub fn reset(&mut self, seed: u64) -> Result<Observation> {
+        if self.seats.len() > 4 {
+            return Err(GemError::ReachedPlayerLimit);
+        }
+        if self.seats.len() < 2 || self.seats.iter().all(Option::is_some) {
+            return Err(GemError::TooFewPlayers);
+        }
+
+        let behaviors = self
+            .seats
+            .iter()
+            .map(|seat| match seat {
+                Some(factory) => factory(),
+                None => Box::new(AgentSeat {
+                    next: self.next.clone(),
+                }),
+            })
+            .collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.game = Some(Game::with_rng(behaviors, &mut rng));
+        self.advance()?;
+        Ok(self.observe())
+    }
+
+    /// Plays the action with the given index for the agent seat making the
+    /// next decision, followed by every opponent decision up to the next
+    /// decision of an agent seat. This function will return an error if no
+    /// game is in progress, if the action is illegal, or if an opponent makes
+    /// an illegal move.
+    pub fn step(&mut self, action: usize) -> Result<StepResult> {
+        let game = self.game.as_mut().ok_or(GemError::GameAlreadyOver)?;
+        if game.info_ref().game_over() {
+            return Err(GemError::GameAlreadyOver);
+        }
+        let next = Action::from_index(action)
+            .and_then(|action| action.resolve(game.info_ref()))
+            .ok_or(GemError::IllegalAction)?;
+
+        *self.next.borrow_mut() = Some(next);
+        game.step()?;
+        let opponent_decisions = self.advance()?;
+
+        let info = self.info().unwrap();
+        let done = info.game_over();
+        let scores = done.then(|| info.scores());
+        let rewards = match scores {
+            Some(scores) => Self::rewards(info.num_players(), &scores),
+            None => vec![0.0; info.num_players()],
+        };
+        Ok(StepResult {
+            observation: self.observe(),
+            rewards,
+            done,
+            info: StepInfo {
+                round_index: info.round_index(),
+                opponent_decisions,
+                scores,
+            },
+        })
+    }
+
+    /// Returns the observation of the player making the next decision. If
+    /// the game has ended the observation is from the first agent seat and
+    /// no action is legal.
+    pub fn observe(&self) -> Observation {
+        let info = self.info().expect("observed an environment before reset");
+        let agent = self.seats.iter().position(Option::is_none).unwrap();
+        let (player, decision) = match info.next_decision() {
+            Some((player, decision)) => (player, Some(decision)),
+            None => (agent, None),
+        };
+        Observation {
+            player,
+            decision,
+            features: FeatureEncoder::encode(info, player),
+            legal: Action::legal_mask(info),
+        }
+    }
+
+    /// Plays opponent decisions until an agent seat has to decide or the game
+    /// has ended, returning the number of decisions made.
+    fn advance(&mut self) -> Result<usize> {
+        let game = self.game.as_mut().unwrap();
+        let mut decisions = 0;
+        while let Some((player, _)) = game.info_ref().next_decision() {
+            if self.seats[player].is_none() {
+                break;
+            }
+            game.step()?;
+            decisions += 1;
+        }
+        Ok(decisions)
+    }
+
+    fn rewards(num_players: usize, scores: &GameScores) -> Vec<f32> {
+        let total = (0..num_players).map(|seat| scores.get(seat)).sum::<i32>();
+        (0..num_players)
+            .map(|seat| {
+                let others = (total - scores.get(seat)) as f32 / (num_players - 1) as f32;
+                scores.get(seat) as f32 - others
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,7 @@
+mod action;
+mod env;
+
+pub use action::{
+    Action, Move, PaymentModel, ACTION_COUNT, NUM_BIDS, NUM_FLIP_SLOTS, NUM_STACK_SLOTS,
+};
+pub use env::{GemEnv, Observation, StepInfo, StepResult};
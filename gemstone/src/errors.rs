@@ -15,6 +15,8 @@ pub enum GemError {
     CannotAffordBid,
     /// Raised when a player tries to pay for a bid with too few gem cards
     TooFewGemCards,
+    /// Raised when a player picks a card outside of the stack
+    TriedToPickMissingCard,
     /// Raised when a player tries to pay with an already-leveraged gem card
     TriedToUseLeveragedCard,
     /// Raised when a player tries to flip a non-leveraged card
@@ -23,11 +25,47 @@ pub enum GemError {
     TriedToFlipCoinCard,
     /// Raised when a player cannot afford to flip the provided cards
     CannotAffortToFlip,
+    /// Raised when an environment is given an action which is not legal in
+    /// the current state
+    IllegalAction,
+    /// Raised when a [`GemNotation`](crate::encoding::GemNotation) does not
+    /// describe a valid position
+    InvalidNotation(String),
+    /// Raised when a [`Transcript`](crate::encoding::Transcript) cannot be
+    /// read, or holds positions which do not follow from each other
+    InvalidTranscript(String),
+    /// Raised when a [`BehaviorRegistry`](crate::game::BehaviorRegistry) is
+    /// asked for a behavior which was never registered
+    UnknownBehavior(String),
+    /// Raised when a [`BehaviorSpec`](crate::game::BehaviorSpec) is malformed
+    /// or has invalid parameters
+    InvalidBehaviorSpec(String),
+    /// Raised when the player with the given index resigns, such as a human
+    /// player closing their input
+    PlayerResigned(usize),
+    /// Raised when a message of the Gem Engine Interface cannot be read, see
+    /// [`EngineCommand`](crate::engine::EngineCommand)
+    InvalidEngineMessage(String),
+    /// Raised when an engine process cannot be started, exits, times out or
+    /// replies with an illegal move
+    EngineFailure(String),
+    /// Raised when a [`BotId`](crate::tournament::BotId) holds a character
+    /// which cannot be written to a rating ledger
+    InvalidBotId(String),
 }
 
 impl Display for GemError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::InvalidNotation(reason) => write!(f, "InvalidNotation: {reason}"),
+            Self::InvalidTranscript(reason) => write!(f, "InvalidTranscript: {reason}"),
+            Self::UnknownBehavior(name) => write!(f, "UnknownBehavior: `{name}`"),
+            Self::InvalidBehaviorSpec(reason) => write!(f, "InvalidBehaviorSpec: {reason}"),
+            Self::InvalidEngineMessage(message) => write!(f, "InvalidEngineMessage: `{message}`"),
+            Self::EngineFailure(reason) => write!(f, "EngineFailure: {reason}"),
+            Self::InvalidBotId(id) => write!(f, "InvalidBotId: `{id}`"),
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
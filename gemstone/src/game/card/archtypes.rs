@@ -47,13 +47,22 @@ impl GemArchtype {
         u8::from(self.0 & 0x0f != GemType::Diamond as u8) + 1
     }
 
-    fn get_gems(self) -> (GemType, GemType) {
+    /// Returns the two gem types of this archtype. Note that the diamond
+    /// archtype returns the diamond type twice, see [`gems`](Self::gems).
+    pub fn get_gems(self) -> (GemType, GemType) {
         (
             GemType::from_index(self.0 >> 4),
             GemType::from_index(self.0 & 0x0f),
         )
     }
 
+    /// Returns an iterator over each gem on this card, such that the diamond
+    /// archtype only yields a single gem.
+    pub fn gems(self) -> impl Iterator<Item = GemType> {
+        let (first, second) = self.get_gems();
+        [first, second].into_iter().take(self.num_gems() as usize)
+    }
+
     pub fn value(self) -> BidValue {
         match self.get_gems() {
             (GemType::Diamond, _) => 2,
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use super::{BidValue, Card};
+
+/// A single decision made during the game, as recorded in the history of the
+/// [`GameInfo`](super::GameInfo).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A player placed a bid, which is a pass unless it exceeds the highest
+    /// bid made before it.
+    Bid {
+        player: usize,
+        bid: BidValue,
+        /// The highest bid before this bid, or `-1` if nobody had bid.
+        highest: BidValue,
+        /// The capital of the player at the time of the bid.
+        capital: BidValue,
+    },
+    /// The highest bidder bought a card from the stack.
+    Purchase {
+        player: usize,
+        card: Card,
+        /// The highest bid, which the payment had to cover.
+        price: BidValue,
+        /// The cards used to pay, before they were leveraged.
+        payment: Vec<Card>,
+    },
+    /// A player flipped cards during the reinvestment phase.
+    Reinvestment {
+        player: usize,
+        /// The flipped cards, before they were flipped.
+        flipped: Vec<Card>,
+    },
+}
+
+impl GameEvent {
+    /// Returns the player who made the decision.
+    pub fn player(&self) -> usize {
+        match *self {
+            Self::Bid { player, .. }
+            | Self::Purchase { player, .. }
+            | Self::Reinvestment { player, .. } => player,
+        }
+    }
+}
+
+/// Every decision made during a game, in the order they were made.
+///
+/// The events are kept in a shared linked list, such that cloning a
+/// [`GameInfo`](super::GameInfo) and pushing to the clone does not copy the
+/// events made before.
+#[derive(Clone, Debug, Default)]
+pub struct GameHistory {
+    last: Option<Arc<HistoryNode>>,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct HistoryNode {
+    event: GameEvent,
+    previous: Option<Arc<HistoryNode>>,
+}
+
+impl GameHistory {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the most recent event, if any.
+    pub fn last(&self) -> Option<&GameEvent> {
+        self.last.as_ref().map(|node| &node.event)
+    }
+
+    /// Iterates over the events in the order they were made.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &GameEvent> + ExactSizeIterator {
+        let mut events = Vec::with_capacity(self.len);
+        let mut node = self.last.as_deref();
+        while let Some(current) = node {
+            events.push(&current.event);
+            node = current.previous.as_deref();
+        }
+        events.into_iter().rev()
+    }
+
+    pub(crate) fn push(&mut self, event: GameEvent) {
+        self.last = Some(Arc::new(HistoryNode {
+            event,
+            previous: self.last.take(),
+        }));
+        self.len += 1;
+    }
+}
@@ -1,21 +1,48 @@
 use std::cell::RefCell;
 
-use crate::{errors::Result, player::PlayerBehavior, GemError};
+use rand::Rng;
 
-use super::{CardIterator, GameInfo, GameScores};
+use crate::{
+    errors::{GemError, Result},
+    player::PlayerBehavior,
+};
+
+use super::{CardCollection, GameInfo, GameScores};
 
 /// The `Game` struct represents a current active game of Gem.
-pub struct Game {
+///
+/// The behaviors are stored as `Box<B>`, which defaults to
+/// `Box<dyn PlayerBehavior>`. A `Game<SendBehavior>` only holds behaviors
+/// which implement `Send`, and can therefore be moved across threads.
+pub struct Game<B: PlayerBehavior + ?Sized = dyn PlayerBehavior> {
     info: GameInfo,
-    behaviors: RefCell<Vec<Box<dyn PlayerBehavior>>>,
+    behaviors: RefCell<Vec<Box<B>>>,
 }
 
-impl Game {
+impl<B: PlayerBehavior + ?Sized> Game<B> {
     /// Create a new `Game` given a number of [`PlayerBehavior`]-implementers.
     /// There must exist at least two and at most four behaviors.
-    pub fn new(behaviors: Vec<Box<dyn PlayerBehavior>>) -> Self {
+    pub fn new(behaviors: Vec<Box<B>>) -> Self {
+        let game_info = GameInfo::new(behaviors.len());
+        Self::with_info(game_info, behaviors)
+    }
+
+    /// Create a new `Game` like [`new`](Self::new), but shuffle the deck using
+    /// the given random number generator.
+    pub fn with_rng(behaviors: Vec<Box<B>>, rng: &mut impl Rng) -> Self {
+        let game_info = GameInfo::with_rng(behaviors.len(), rng);
+        Self::with_info(game_info, behaviors)
+    }
+
+    /// Create a new `Game` like [`new`](Self::new), but use the given deck
+    /// without shuffling it.
+    pub fn with_deck(behaviors: Vec<Box<B>>, deck: CardCollection<18>) -> Self {
+        let game_info = GameInfo::with_deck(behaviors.len(), deck);
+        Self::with_info(game_info, behaviors)
+    }
+
+    fn with_info(mut game_info: GameInfo, behaviors: Vec<Box<B>>) -> Self {
         assert!(behaviors.len() >= 2 && behaviors.len() <= 4);
-        let mut game_info = GameInfo::new(behaviors.len());
         game_info.prepare_auction();
         Self {
             info: game_info,
@@ -49,78 +76,49 @@ impl Game {
         Ok(None)
     }
 
-    // TODO: docs
+    /// Asks the current player for a bid, or the highest bidder for a card
+    /// once everyone has bid.
     fn step_auction(&mut self) -> Result<()> {
         if !self.info.round_over() {
             let idx = self.info.current_player();
-            let bid = self.behaviors.borrow_mut()[idx].bid(self.info_ref()).max(0);
-
-            if self.info.inventory_at(idx).iter().capital() < bid {
-                return Err(GemError::CannotAffordBid);
-            }
-            if bid > self.info.highest_bid() {
-                self.info.set_highest_bid(bid, idx);
-            }
-
-            self.info.increment_player();
+            let bid = self.behaviors.borrow_mut()[idx].bid(self.info_ref());
+            self.check_resigned(idx)?;
+            self.info.apply_bid(bid)
         } else {
             let idx = self.info.highest_bidder();
             self.info.set_current_player(idx);
-            let inv = self.info.inventory_at(idx);
-            let (mut card_idx, payment_choice) =
+            let (card_idx, payment_choice) =
                 self.behaviors.borrow_mut()[idx].pick_card(self.info_ref());
-            card_idx = card_idx.min(self.info.stack_size());
-
-            if inv.choose(payment_choice).leveraged().count() != 0 {
-                return Err(GemError::TriedToUseLeveragedCard);
-            }
-            if inv.choose(payment_choice).scalar_value() < self.info.highest_bid() {
-                return Err(GemError::CannotAffordBid);
-            }
-
-            self.info.buy_card(card_idx, idx, payment_choice);
-            self.info
-                .start_step_cycle(self.info.next_clockwise_player(self.info.highest_bidder()));
-        }
-
-        if self.info.is_reinvestment_phase() {
-            self.info.start_step_cycle(self.info.highest_bidder());
+            self.check_resigned(idx)?;
+            self.info.apply_pick(card_idx, payment_choice)
         }
-
-        Ok(())
     }
 
-    // TODO: docs
+    /// Asks the current player which cards to flip.
     fn step_reinvestment(&mut self) -> Result<()> {
-        let idx = self.info.current_player() as usize;
+        let idx = self.info.current_player();
         let choices = self.behaviors.borrow_mut()[idx].reinvest(self.info_ref());
+        self.check_resigned(idx)?;
+        self.info.apply_reinvest(choices)
+    }
 
-        let current_inv = self.info.inventory_at(idx);
-        if current_inv.choose(choices).scalar_value() < 0 {
-            return Err(GemError::CannotAffortToFlip);
-        }
-        self.info.flip_cards(idx, choices);
-
-        self.info.increment_player();
-
-        // reinvestment phase only has one round
-        if self.info.round_over() {
-            self.info.reset_coin_cards();
-
-            self.info.increment_round_index();
-            self.info
-                .start_step_cycle(self.info.next_clockwise_player(self.info.highest_bidder()));
-
-            if !self.info.game_over() {
-                self.info.prepare_auction();
-            }
+    fn check_resigned(&self, idx: usize) -> Result<()> {
+        match self.behaviors.borrow()[idx].resigned() {
+            true => Err(GemError::PlayerResigned(idx)),
+            false => Ok(()),
         }
-
-        Ok(())
     }
 
     /// Returns a reference to the [`GameInfo`].
     pub fn info_ref(&self) -> &GameInfo {
         &self.info
     }
+
+    /// Replaces the current position, such as to take back decisions. The
+    /// position must have the same number of players, and the behaviors are
+    /// not notified.
+    pub fn restore(&mut self, info: GameInfo) {
+        assert_eq!(info.num_players(), self.info.num_players());
+        self.info = info;
+    }
 }
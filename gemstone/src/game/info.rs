@@ -1,14 +1,36 @@
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 
-use crate::{player::PlayerInventory, GemNotation};
+use crate::{errors::Result, player::PlayerInventory, GemError, GemNotation};
 
-use super::{BidValue, Card, CardChoice, CardCollection, CardIterator};
+use super::{
+    BidValue, Card, CardChoice, CardCollection, CardIterator, GameEvent, GameHistory, GemType,
+    Majority, MajorityTracker,
+};
 
 /// Represents the final game scores for each of the possible players.
 #[derive(Default, Debug, Clone, Copy)]
-#[allow(unused)]
 pub struct GameScores([i32; 4]);
 
+impl GameScores {
+    /// Returns the final score of the given player.
+    #[inline]
+    pub fn get(&self, player: usize) -> i32 {
+        self.0[player]
+    }
+}
+
+/// The kind of decision a player has to make next, see
+/// [`GameInfo::next_decision`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// Place a bid on the current auction.
+    Bid,
+    /// Pick a card from the stack and pay for it, after winning the auction.
+    PickCard,
+    /// Choose which cards to flip in the reinvestment phase.
+    Reinvest,
+}
+
 /// The `GameInfo` holds all variables necessary to represent a unqiue
 /// game-state, but unlike the `Game`-struct this does not have any functions
 /// to autonomously progress the state of the game and is only meant for
@@ -40,6 +62,11 @@ pub struct GameInfo {
     /// all cards in the stack are considered [`null`](`Card::NULL`), otherwise
     /// the game is in the auction phase.
     stack: CardCollection<4>,
+    /// The gem counts of all players, kept up to date as cards are bought
+    /// and flipped.
+    majorities: MajorityTracker,
+    /// Every decision made so far, in the order they were made.
+    history: GameHistory,
 }
 
 //
@@ -51,8 +78,20 @@ impl GameInfo {
     /// initialises all fields to their respective defaults, such as setting
     /// the inventories to have coins and creating a shuffled deck of 18 cards.
     pub fn new(num_players: usize) -> Self {
+        Self::with_rng(num_players, &mut thread_rng())
+    }
+
+    /// Creates a new `GameInfo` like [`new`](Self::new), but shuffles the deck
+    /// using the given random number generator.
+    pub fn with_rng(num_players: usize, rng: &mut impl Rng) -> Self {
         let mut deck = Card::gem_deck();
-        deck.shuffle(&mut thread_rng());
+        deck.shuffle(rng);
+        Self::with_deck(num_players, deck)
+    }
+
+    /// Creates a new `GameInfo` like [`new`](Self::new), but uses the given
+    /// deck without shuffling it.
+    pub fn with_deck(num_players: usize, deck: CardCollection<18>) -> Self {
         Self {
             num_players,
             round_index: 0,
@@ -64,12 +103,72 @@ impl GameInfo {
             inventories: Default::default(),
             deck,
             stack: Default::default(),
+            majorities: Default::default(),
+            history: GameHistory::default(),
         }
     }
 
-    pub fn from_notation(notation: GemNotation) -> Self {
+    /// Creates a `GameInfo` from a position written in [`GemNotation`]. This
+    /// function will return an error if the notation is invalid.
+    pub fn from_notation(notation: &GemNotation) -> Result<Self> {
         notation.to_info()
     }
+
+    /// Creates a `GameInfo` from a position given by the stack, the
+    /// inventories, the highest bid and the `[current, starting, highest]`
+    /// bidder indices. The round is derived from the number of cards dealt,
+    /// and the cards not yet dealt are placed in the deck in their default
+    /// order. Returns `None` if the cards cannot be dealt in a game with the
+    /// given number of players.
+    pub(crate) fn from_position(
+        num_players: usize,
+        stack: CardCollection<4>,
+        inventories: [PlayerInventory; 4],
+        highest_bid: BidValue,
+        [current, starting, highest]: [usize; 3],
+    ) -> Option<Self> {
+        let dealt = inventories[..num_players]
+            .iter()
+            .flat_map(|inv| inv.iter().gem_cards().cloned())
+            .chain(stack.iter().cloned())
+            .collect::<Vec<_>>();
+
+        // the dealt cards come first, followed by the remaining cards
+        let mut remaining = Card::gem_deck().iter().cloned().collect::<Vec<_>>();
+        for card in &dealt {
+            let idx = remaining
+                .iter()
+                .position(|other| other.archtype() == card.archtype())?;
+            remaining.remove(idx);
+        }
+        let mut cards = [Card::NULL; 18];
+        dealt
+            .iter()
+            .chain(&remaining)
+            .zip(cards.iter_mut())
+            .for_each(|(card, slot)| *slot = card.with_leverage(true));
+
+        let mut dealt_before = 0;
+        let round_index = Self::stack_sizes(num_players).iter().position(|&size| {
+            dealt_before += size;
+            dealt_before == dealt.len()
+        })?;
+
+        let mut info = Self::with_deck(num_players, CardCollection::new(cards));
+        info.round_index = round_index;
+        info.stack = stack;
+        info.inventories = inventories;
+        info.majorities = MajorityTracker::from_info(&info);
+        info.starting_player = starting;
+        info.current_player = current;
+        info.highest_bid = highest_bid;
+        info.highest_bidder = highest;
+        // the highest bidder is asked for a card once everyone has bid
+        info.round_over = info.is_auction_phase()
+            && highest_bid >= 0
+            && (current == starting || current == highest);
+        Some(info)
+    }
 }
 
 //
@@ -152,6 +251,18 @@ impl GameInfo {
     pub fn stack_size(&self) -> usize {
         self.stack.len()
     }
+
+    /// Returns the [`MajorityTracker`] holding the gem counts of all players.
+    #[inline]
+    pub fn majorities(&self) -> &MajorityTracker {
+        &self.majorities
+    }
+
+    /// Returns every decision made so far, in the order they were made.
+    #[inline]
+    pub fn history(&self) -> &GameHistory {
+        &self.history
+    }
 }
 
 //
@@ -175,14 +286,22 @@ impl GameInfo {
     }
 
     pub fn prepare_auction(&mut self) {
-        let stack_sizes = match self.num_players {
-            3 => [3, 3, 3, 3, 3, 3],
-            _ => [4, 3, 3, 3, 3, 2],
-        };
+        let stack_sizes = Self::stack_sizes(self.num_players);
         let round_index = self.round_index;
         let idx = stack_sizes[..round_index].iter().sum::<usize>();
         let size = stack_sizes[round_index];
         self.stack.copy_from(&self.deck, idx..idx + size, 0..size);
+        for &card in self.stack.iter() {
+            self.majorities.reveal(card);
+        }
+    }
+
+    /// Returns the number of cards dealt to the stack in each round.
+    fn stack_sizes(num_players: usize) -> [usize; 6] {
+        match num_players {
+            3 => [3, 3, 3, 3, 3, 3],
+            _ => [4, 3, 3, 3, 3, 2],
+        }
     }
 
     #[inline]
@@ -207,6 +326,118 @@ impl GameInfo {
     pub fn game_over(&self) -> bool {
         self.round_index > 5
     }
+
+    /// Returns the player making the next decision together with the kind of
+    /// decision, or `None` if the game has ended.
+    pub fn next_decision(&self) -> Option<(usize, Decision)> {
+        if self.game_over() {
+            return None;
+        }
+        Some(match (self.is_auction_phase(), self.round_over) {
+            (true, false) => (self.current_player, Decision::Bid),
+            (true, true) => (self.highest_bidder, Decision::PickCard),
+            (false, _) => (self.current_player, Decision::Reinvest),
+        })
+    }
+}
+
+//
+// Decisions
+//
+
+impl GameInfo {
+    /// Applies a bid by the current player, where negative bids are treated
+    /// as passing. This function will return an error if the player cannot
+    /// afford the bid.
+    pub fn apply_bid(&mut self, bid: BidValue) -> Result<()> {
+        let idx = self.current_player;
+        let bid = bid.max(0);
+        let capital = self.inventories[idx].iter().capital();
+        if capital < bid {
+            return Err(GemError::CannotAffordBid);
+        }
+        self.history.push(GameEvent::Bid {
+            player: idx,
+            bid,
+            highest: self.highest_bid,
+            capital,
+        });
+        if bid > self.highest_bid {
+            self.set_highest_bid(bid, idx);
+        }
+        self.increment_player();
+        self.finish_auction();
+        Ok(())
+    }
+
+    /// Applies the purchase of the card at `card_idx` in the stack by the
+    /// highest bidder, paid for with `payment_choice`. This function will
+    /// return an error if `card_idx` is outside of the stack, or if the
+    /// payment contains leveraged cards or does not cover the highest bid.
+    pub fn apply_pick(&mut self, card_idx: usize, payment_choice: CardChoice) -> Result<()> {
+        let idx = self.highest_bidder;
+        self.current_player = idx;
+        if card_idx >= self.stack_size() {
+            return Err(GemError::TriedToPickMissingCard);
+        }
+
+        let inv = &self.inventories[idx];
+        if inv.choose(payment_choice).leveraged().count() != 0 {
+            return Err(GemError::TriedToUseLeveragedCard);
+        }
+        if inv.choose(payment_choice).scalar_value() < self.highest_bid {
+            return Err(GemError::CannotAffordBid);
+        }
+
+        self.history.push(GameEvent::Purchase {
+            player: idx,
+            card: self.stack.as_ref()[card_idx],
+            price: self.highest_bid,
+            payment: inv.choose(payment_choice).cloned().collect(),
+        });
+        self.buy_card(card_idx, idx, payment_choice);
+        self.start_step_cycle(self.next_clockwise_player(idx));
+        self.finish_auction();
+        Ok(())
+    }
+
+    /// Applies the reinvestment of the current player, flipping every chosen
+    /// card. Once every player has reinvested the next round is prepared.
+    /// This function will return an error if the player cannot afford to
+    /// flip the chosen cards.
+    pub fn apply_reinvest(&mut self, choices: CardChoice) -> Result<()> {
+        let idx = self.current_player;
+        if self.inventories[idx].choose(choices).scalar_value() < 0 {
+            return Err(GemError::CannotAffortToFlip);
+        }
+        self.history.push(GameEvent::Reinvestment {
+            player: idx,
+            flipped: self.inventories[idx].choose(choices).cloned().collect(),
+        });
+        self.flip_cards(idx, choices);
+        self.increment_player();
+
+        // reinvestment phase only has one round
+        if self.round_over {
+            self.reset_coin_cards();
+
+            self.increment_round_index();
+            self.start_step_cycle(self.next_clockwise_player(self.highest_bidder));
+
+            if !self.game_over() {
+                self.prepare_auction();
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts the reinvestment phase with the highest bidder once the stack
+    /// has been bought out.
+    fn finish_auction(&mut self) {
+        if self.is_reinvestment_phase() {
+            self.start_step_cycle(self.highest_bidder);
+        }
+    }
 }
 
 //
@@ -217,15 +448,19 @@ impl GameInfo {
     pub fn buy_card(&mut self, card_idx: usize, player_idx: usize, payment_choice: CardChoice) {
         let card = self.stack.pop(card_idx);
         self.inventories[player_idx].push_back(card);
-        self.inventories[player_idx]
-            .choose_mut(payment_choice)
-            .for_each(|card| *card = card.with_leverage(true));
+        self.majorities.add_card(player_idx, card);
+        for card in self.inventories[player_idx].choose_mut(payment_choice) {
+            self.majorities.set_leverage(player_idx, *card, true);
+            *card = card.with_leverage(true);
+        }
     }
 
     pub fn flip_cards(&mut self, player: usize, choices: CardChoice) {
-        self.inventories[player]
-            .choose_mut(choices)
-            .for_each(|card| *card = card.with_leverage(!card.is_leveraged()));
+        for card in self.inventories[player].choose_mut(choices) {
+            self.majorities
+                .set_leverage(player, *card, !card.is_leveraged());
+            *card = card.with_leverage(!card.is_leveraged());
+        }
     }
 
     pub fn reset_coin_cards(&mut self) {
@@ -255,13 +490,15 @@ impl GameInfo {
                 .sum();
         }
 
-        todo!();
-
         // two points for each shared majority
         // three points for each owned majority
-        // for gem_type in GemType::iter() {
-        //     let mut players = [0; 4];
-        // }
+        for gem_type in GemType::iter() {
+            match self.majorities.majority(gem_type) {
+                Majority::Nobody => {}
+                Majority::Owned(player) => scores[player] += 3,
+                Majority::Shared(players) => players.iter().for_each(|&player| scores[player] += 2),
+            }
+        }
         GameScores(scores)
     }
 }
@@ -276,3 +513,78 @@ impl GameInfo {
         &self.inventories[self.current_player]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owned_majorities_score_three_and_shared_majorities_two() {
+        // amethyst is owned, emerald is shared and sapphire is owned, while
+        // the leveraged diamond scores nothing
+        let info = "-//cf123AE;123SE!D;123"
+            .parse::<GemNotation>()
+            .unwrap()
+            .to_info()
+            .unwrap();
+        let scores = info.scores();
+        assert_eq!(scores.get(0), 2 + 3 + 2);
+        assert_eq!(scores.get(1), 2 + 2 + 3);
+        assert_eq!(scores.get(2), 0);
+
+        let info = "-//cf123AED;123SE;123"
+            .parse::<GemNotation>()
+            .unwrap()
+            .to_info()
+            .unwrap();
+        assert_eq!(info.scores().get(0), 3 + 3 + 2 + 3);
+    }
+
+    #[test]
+    fn picks_outside_of_the_stack_are_rejected() {
+        let mut info = GameInfo::with_deck(3, Card::gem_deck());
+        info.prepare_auction();
+        for bid in [1, 0, 0] {
+            info.apply_bid(bid).unwrap();
+        }
+        let size = info.stack_size();
+        let coin = CardChoice::new(&[0]);
+        assert!(matches!(
+            info.apply_pick(size, coin),
+            Err(GemError::TriedToPickMissingCard)
+        ));
+        assert_eq!(info.stack_size(), size);
+
+        info.apply_pick(size - 1, coin).unwrap();
+        assert_eq!(info.stack_size(), size - 1);
+    }
+
+    #[test]
+    fn clones_keep_their_own_history() {
+        let mut info = GameInfo::with_deck(3, Card::gem_deck());
+        info.prepare_auction();
+        info.apply_bid(2).unwrap();
+
+        let mut clone = info.clone();
+        clone.apply_bid(3).unwrap();
+        info.apply_bid(0).unwrap();
+        info.apply_bid(1).unwrap();
+
+        let bids = |info: &GameInfo| {
+            info.history()
+                .iter()
+                .map(|event| match event {
+                    GameEvent::Bid { bid, .. } => *bid,
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bids(&info), [2, 0, 1]);
+        assert_eq!(bids(&clone), [2, 3]);
+        assert_eq!(clone.history().len(), 2);
+        assert!(matches!(
+            info.history().last(),
+            Some(GameEvent::Bid { player: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+use super::{Card, CardIterator, GameInfo, GemType};
+
+/// The number of gems of a single [`GemType`] owned by a player.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct GemCount {
+    pub leveraged: u8,
+    pub non_leveraged: u8,
+}
+
+impl GemCount {
+    /// Returns the number of gems regardless of leverage.
+    #[inline]
+    pub fn total(self) -> u8 {
+        self.leveraged + self.non_leveraged
+    }
+}
+
+/// The holder of the majority of a single [`GemType`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Majority {
+    /// No player owns any gems of the type.
+    Nobody,
+    /// A single player owns more gems of the type than any other player.
+    Owned(usize),
+    /// Multiple players are tied for the most gems of the type.
+    Shared(Vec<usize>),
+}
+
+impl Majority {
+    /// Returns whether the given player holds or shares this majority.
+    pub fn holds(&self, player: usize) -> bool {
+        match self {
+            Majority::Nobody => false,
+            Majority::Owned(holder) => *holder == player,
+            Majority::Shared(holders) => holders.contains(&player),
+        }
+    }
+}
+
+/// Keeps track of the number of gems of each [`GemType`] owned by each player,
+/// and of how many gems of each type have been revealed. The tracker is owned
+/// by the [`GameInfo`] and updated as cards are bought and flipped.
+#[derive(Clone, Debug, Default)]
+pub struct MajorityTracker {
+    /// The gem counts of each player, indexed by player and then gem type.
+    counts: [[GemCount; 6]; 4],
+    /// The number of gems of each type which have been drawn onto the stack.
+    revealed: [u8; 6],
+}
+
+impl MajorityTracker {
+    /// Recomputes a tracker from the inventories and stack of a [`GameInfo`].
+    /// Note that cards already bought are assumed to have been revealed.
+    pub fn from_info(info: &GameInfo) -> Self {
+        let mut tracker = Self::default();
+        for (player, inv) in info.inventories().iter().enumerate() {
+            for &card in inv.iter().gem_cards() {
+                tracker.reveal(card);
+                tracker.add_card(player, card);
+            }
+        }
+        info.stack().iter().for_each(|&card| tracker.reveal(card));
+        tracker
+    }
+
+    /// Returns the gem count of a single gem type for the given player.
+    #[inline]
+    pub fn count(&self, player: usize, gem: GemType) -> GemCount {
+        self.counts[player][gem as usize]
+    }
+
+    /// Returns the gem counts of the given player, indexed by gem type.
+    #[inline]
+    pub fn counts(&self, player: usize) -> &[GemCount; 6] {
+        &self.counts[player]
+    }
+
+    /// Returns the current majority of a gem type, which only takes
+    /// non-leveraged gems into account.
+    pub fn majority(&self, gem: GemType) -> Majority {
+        self.majority_by(gem, |count| count.non_leveraged)
+    }
+
+    /// Returns the majority of a gem type if every card was non-leveraged.
+    pub fn potential_majority(&self, gem: GemType) -> Majority {
+        self.majority_by(gem, GemCount::total)
+    }
+
+    /// Returns the number of gems of a gem type which have not been drawn yet.
+    pub fn unseen(&self, gem: GemType) -> u8 {
+        let total = Card::gem_deck()
+            .iter()
+            .flat_map(|card| card.archtype().gems())
+            .filter(|&other| other == gem)
+            .count() as u8;
+        total - self.revealed[gem as usize]
+    }
+
+    fn majority_by(&self, gem: GemType, count: impl Fn(GemCount) -> u8) -> Majority {
+        let counts = self
+            .counts
+            .iter()
+            .map(|counts| count(counts[gem as usize]))
+            .collect::<Vec<_>>();
+        let best = counts.iter().cloned().max().unwrap_or(0);
+        let holders = (0..counts.len())
+            .filter(|&player| counts[player] == best)
+            .collect::<Vec<_>>();
+        match (best, holders.len()) {
+            (0, _) => Majority::Nobody,
+            (_, 1) => Majority::Owned(holders[0]),
+            _ => Majority::Shared(holders),
+        }
+    }
+}
+
+//
+// Updating
+//
+
+impl MajorityTracker {
+    /// Adds a gem card to the inventory of a player. Coin cards are ignored.
+    pub fn add_card(&mut self, player: usize, card: Card) {
+        if card.is_coin() {
+            return;
+        }
+        for gem in card.archtype().gems() {
+            let count = &mut self.counts[player][gem as usize];
+            match card.is_leveraged() {
+                true => count.leveraged += 1,
+                false => count.non_leveraged += 1,
+            }
+        }
+    }
+
+    /// Updates the counts when a card of a player changes its leverage, where
+    /// `card` is the card before the change. Coin cards are ignored.
+    pub fn set_leverage(&mut self, player: usize, card: Card, leverage: bool) {
+        if card.is_coin() || card.is_leveraged() == leverage {
+            return;
+        }
+        for gem in card.archtype().gems() {
+            let count = &mut self.counts[player][gem as usize];
+            match leverage {
+                true => {
+                    count.non_leveraged -= 1;
+                    count.leveraged += 1;
+                }
+                false => {
+                    count.leveraged -= 1;
+                    count.non_leveraged += 1;
+                }
+            }
+        }
+    }
+
+    /// Marks a card as drawn from the deck. Coin cards are ignored.
+    pub fn reveal(&mut self, card: Card) {
+        if card.is_coin() {
+            return;
+        }
+        card.archtype()
+            .gems()
+            .for_each(|gem| self.revealed[gem as usize] += 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GemArchtype;
+
+    fn card(archtype: u8, leveraged: bool) -> Card {
+        Card::gem(GemArchtype::from_index(archtype)).with_leverage(leveraged)
+    }
+
+    #[test]
+    fn majorities_follow_leverage() {
+        let (ae, ee) = (card(2, false), card(4, true));
+        let mut tracker = MajorityTracker::default();
+        tracker.add_card(0, ae);
+        tracker.add_card(1, ee);
+        tracker.add_card(1, Card::coin(3));
+
+        assert_eq!(
+            tracker.count(1, GemType::Emerald),
+            GemCount {
+                leveraged: 2,
+                non_leveraged: 0
+            }
+        );
+        assert_eq!(tracker.majority(GemType::Emerald), Majority::Owned(0));
+        assert_eq!(
+            tracker.potential_majority(GemType::Emerald),
+            Majority::Owned(1)
+        );
+        assert_eq!(tracker.majority(GemType::Ruby), Majority::Nobody);
+
+        tracker.set_leverage(1, ee, false);
+        assert_eq!(tracker.majority(GemType::Emerald), Majority::Owned(1));
+        tracker.add_card(2, card(4, false));
+        assert_eq!(
+            tracker.majority(GemType::Emerald),
+            Majority::Shared(vec![1, 2])
+        );
+        assert!(tracker.majority(GemType::Emerald).holds(2));
+    }
+
+    #[test]
+    fn unseen_gems_exclude_revealed_cards() {
+        let mut tracker = MajorityTracker::default();
+        assert_eq!(tracker.unseen(GemType::Emerald), 6);
+        assert_eq!(tracker.unseen(GemType::Diamond), 3);
+        tracker.reveal(card(4, true));
+        tracker.reveal(Card::coin(2));
+        assert_eq!(tracker.unseen(GemType::Emerald), 4);
+    }
+}
@@ -1,11 +1,17 @@
 mod card;
+mod event;
 mod game;
 mod info;
+mod majority;
+mod registry;
 mod setup;
 
 pub use card::*;
+pub use event::{GameEvent, GameHistory};
 pub use game::Game;
-pub use info::{GameInfo, GameScores};
+pub use info::{Decision, GameInfo, GameScores};
+pub use majority::{GemCount, Majority, MajorityTracker};
+pub use registry::{BehaviorConstructor, BehaviorParams, BehaviorRegistry, BehaviorSpec};
 pub use setup::GameSetup;
 
 pub type BidValue = i8;
@@ -0,0 +1,177 @@
+use std::{str::FromStr, sync::Arc};
+
+use crate::{
+    errors::{GemError, Result},
+    player::{SendBehavior, SendBehaviorFactory},
+};
+
+/// A function creating a behavior from the parameters of a spec.
+pub type BehaviorConstructor =
+    Box<dyn Fn(&mut BehaviorParams) -> Result<Box<SendBehavior>> + Send + Sync>;
+
+/// A behavior name with parameters, written as `name` or
+/// `name:key=value,key=value`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BehaviorSpec {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl FromStr for BehaviorSpec {
+    type Err = GemError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| GemError::InvalidBehaviorSpec(format!("{reason} in `{s}`"));
+
+        let (name, params) = s.trim().split_once(':').unwrap_or((s.trim(), ""));
+        if name.is_empty() {
+            return Err(invalid("missing name"));
+        }
+        let params = params
+            .split(',')
+            .filter(|param| !param.trim().is_empty())
+            .map(|param| match param.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    Ok((key.trim().to_string(), value.trim().to_string()))
+                }
+                _ => Err(invalid("expected `key=value`")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            name: name.to_string(),
+            params,
+        })
+    }
+}
+
+/// The parameters given to a [`BehaviorConstructor`]. Every parameter must be
+/// read by the constructor, such that misspelled keys are reported.
+pub struct BehaviorParams {
+    name: String,
+    params: Vec<(String, String)>,
+}
+
+impl BehaviorParams {
+    /// Takes the parameter with the given key, or returns `default` if it was
+    /// not given. This function will return an error if the value cannot be
+    /// parsed.
+    pub fn get<T: FromStr>(&mut self, key: &str, default: T) -> Result<T> {
+        Ok(self.get_opt(key)?.unwrap_or(default))
+    }
+
+    /// Takes the parameter with the given key, if it was given. This function
+    /// will return an error if the value cannot be parsed.
+    pub fn get_opt<T: FromStr>(&mut self, key: &str) -> Result<Option<T>> {
+        let idx = match self.params.iter().position(|(other, _)| other == key) {
+            Some(idx) => idx,
+            None => return Ok(None),
+        };
+        let (_, value) = self.params.remove(idx);
+        value.parse().map(Some).map_err(|_| {
+            GemError::InvalidBehaviorSpec(format!(
+                "invalid value `{value}` for `{key}` of `{}`",
+                self.name
+            ))
+        })
+    }
+
+    /// Takes every parameter whose key starts with `prefix`, such as the
+    /// options `option.depth=3` of an engine, with the prefix removed.
+    pub fn take_prefixed(&mut self, prefix: &str) -> Vec<(String, String)> {
+        let mut taken = Vec::new();
+        self.params
+            .retain(|(key, value)| match key.strip_prefix(prefix) {
+                Some(key) => {
+                    taken.push((key.to_string(), value.clone()));
+                    false
+                }
+                None => true,
+            });
+        taken
+    }
+
+    fn finish(self) -> Result<()> {
+        match self.params.first() {
+            Some((key, _)) => Err(GemError::InvalidBehaviorSpec(format!(
+                "unknown parameter `{key}` for `{}`",
+                self.name
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    description: String,
+    constructor: BehaviorConstructor,
+}
+
+/// Maps behavior names to constructors, such that behaviors can be selected
+/// by a [`BehaviorSpec`] from the command line or a configuration file.
+#[derive(Default)]
+pub struct BehaviorRegistry {
+    entries: Vec<Entry>,
+}
+
+impl BehaviorRegistry {
+    /// Registers a constructor under the given name, replacing any constructor
+    /// previously registered under the same name.
+    pub fn register(
+        &mut self,
+        name: &str,
+        description: &str,
+        constructor: impl Fn(&mut BehaviorParams) -> Result<Box<SendBehavior>> + Send + Sync + 'static,
+    ) {
+        self.entries.retain(|entry| entry.name != name);
+        self.entries.push(Entry {
+            name: name.to_string(),
+            description: description.to_string(),
+            constructor: Box::new(constructor),
+        });
+    }
+
+    /// Returns the name and description of every registered behavior, in
+    /// registration order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.description.as_str()))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|entry| entry.name == name)
+    }
+
+    /// Creates a behavior from a spec such as `heuristic:bid_rate=0.7`. This
+    /// function will return an error if the name is unknown or the
+    /// parameters are invalid.
+    pub fn create(&self, spec: &str) -> Result<Box<SendBehavior>> {
+        self.create_from(&spec.parse()?)
+    }
+
+    pub fn create_from(&self, spec: &BehaviorSpec) -> Result<Box<SendBehavior>> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| entry.name == spec.name)
+            .ok_or_else(|| GemError::UnknownBehavior(spec.name.clone()))?;
+        let mut params = BehaviorParams {
+            name: spec.name.clone(),
+            params: spec.params.clone(),
+        };
+        let behavior = (entry.constructor)(&mut params)?;
+        params.finish()?;
+        Ok(behavior)
+    }
+
+    /// Returns a factory creating a new behavior from the spec for every game.
+    /// The spec is checked once, and this function will return an error if
+    /// the behavior cannot be created.
+    pub fn factory(self: &Arc<Self>, spec: &BehaviorSpec) -> Result<SendBehaviorFactory> {
+        self.create_from(spec)?;
+        let spec = spec.clone();
+        let registry = self.clone();
+        Ok(Box::new(move || registry.create_from(&spec).unwrap()))
+    }
+}
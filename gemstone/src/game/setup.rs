@@ -1,16 +1,18 @@
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, SeedableRng};
 
 use crate::{
     errors::{GemError, Result},
     player::PlayerBehavior,
 };
 
-use super::Game;
+use super::{BehaviorRegistry, CardCollection, Game};
 
 /// A struct representing the setup-phase of the game.
 #[derive(Default)]
 pub struct GameSetup {
     behaviors: Vec<Box<dyn PlayerBehavior>>,
+    seed: Option<u64>,
+    deck: Option<CardCollection<18>>,
 }
 
 impl GameSetup {
@@ -29,11 +31,30 @@ impl GameSetup {
         Ok(())
     }
 
+    /// Adds the behavior described by a spec such as `heuristic:bid_rate=0.7`,
+    /// see [`BehaviorRegistry::create`]. This function will return an error
+    /// if the behavior cannot be created or the player limit is reached.
+    pub fn add_named_player(&mut self, registry: &BehaviorRegistry, spec: &str) -> Result<()> {
+        self.insert_player(registry.create(spec)?)
+    }
+
     /// Shuffles the playing order, such that any player have an equal chance to start.
     pub fn shuffle_players(&mut self) {
         self.behaviors.shuffle(&mut thread_rng());
     }
 
+    /// Sets the seed used to shuffle the deck, such that games with the same
+    /// seed and number of players are dealt the same cards.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    /// Sets the order of the deck, which takes precedence over any seed set
+    /// with [`set_seed`](Self::set_seed).
+    pub fn set_deck(&mut self, deck: CardCollection<18>) {
+        self.deck = Some(deck);
+    }
+
     /// Finish the setup-phase and get the actual [`Game`]-struct. This
     /// function will return an error if the number of players is less
     /// than two.
@@ -41,6 +62,10 @@ impl GameSetup {
         if self.behaviors.len() < 2 {
             return Err(GemError::TooFewPlayers);
         }
-        Ok(Game::new(self.behaviors))
+        Ok(match (self.deck, self.seed) {
+            (Some(deck), _) => Game::with_deck(self.behaviors, deck),
+            (None, Some(seed)) => Game::with_rng(self.behaviors, &mut StdRng::seed_from_u64(seed)),
+            (None, None) => Game::new(self.behaviors),
+        })
     }
 }
@@ -1,9 +1,17 @@
+mod analysis;
 mod encoding;
+mod engine;
+mod environment;
 mod errors;
 mod game;
 mod player;
+mod tournament;
 
+pub use crate::analysis::*;
 pub use crate::encoding::*;
+pub use crate::engine::*;
+pub use crate::environment::*;
 pub use crate::errors::{GemError, Result};
 pub use crate::game::*;
 pub use crate::player::*;
+pub use crate::tournament::*;
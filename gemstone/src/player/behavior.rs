@@ -3,6 +3,14 @@ use crate::{
     BidValue,
 };
 
+/// A [`PlayerBehavior`] which can be sent across threads, see
+/// [`Game`](crate::Game).
+pub type SendBehavior = dyn PlayerBehavior + Send;
+
+/// A function creating a new instance of a [`SendBehavior`] for every game,
+/// which may be called from any thread.
+pub type SendBehaviorFactory = Box<dyn Fn() -> Box<SendBehavior> + Send + Sync>;
+
 pub trait PlayerBehavior {
     /// TODO: write documentation
     fn bid(&mut self, info: &GameInfo) -> BidValue;
@@ -10,4 +18,29 @@ pub trait PlayerBehavior {
     fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice);
     /// TODO: write documentation
     fn reinvest(&mut self, info: &GameInfo) -> CardChoice;
+    /// Returns whether the player has resigned, which is checked after every
+    /// decision. A [`Game`](crate::Game) ends with
+    /// [`GemError::PlayerResigned`](crate::GemError::PlayerResigned) once a
+    /// player resigns, ignoring the decision made.
+    fn resigned(&self) -> bool {
+        false
+    }
+}
+
+impl<T: PlayerBehavior + ?Sized> PlayerBehavior for Box<T> {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        self.as_mut().bid(info)
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        self.as_mut().pick_card(info)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        self.as_mut().reinvest(info)
+    }
+
+    fn resigned(&self) -> bool {
+        self.as_ref().resigned()
+    }
 }
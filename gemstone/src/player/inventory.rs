@@ -33,3 +33,10 @@ impl Default for PlayerInventory {
         inv
     }
 }
+
+impl PlayerInventory {
+    /// Creates an inventory without any cards, not even the coin cards.
+    pub(crate) fn empty() -> Self {
+        PlayerInventory(CardCollection::default())
+    }
+}
@@ -1,5 +1,5 @@
 mod behavior;
 mod inventory;
 
-pub use behavior::PlayerBehavior;
+pub use behavior::{PlayerBehavior, SendBehavior, SendBehaviorFactory};
 pub use inventory::PlayerInventory;
@@ -0,0 +1,270 @@
+use std::fmt::Display;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    errors::{GemError, Result},
+    game::{Card, GameSetup},
+    player::PlayerBehavior,
+};
+
+use super::BehaviorFactory;
+
+/// The results of a [`DuplicateMatch`].
+#[derive(Clone, Debug)]
+pub struct DuplicateResults {
+    names: Vec<String>,
+    /// The summed score of each entrant over all rotations of each complete
+    /// deal, indexed first by deal and then by entrant.
+    deal_scores: Vec<Vec<i32>>,
+    /// The number of deals skipped because a game ended with an error.
+    errors: u32,
+}
+
+impl DuplicateResults {
+    #[inline]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the summed scores of each complete deal, indexed first by deal
+    /// and then by entrant.
+    #[inline]
+    pub fn deal_scores(&self) -> &[Vec<i32>] {
+        &self.deal_scores
+    }
+
+    #[inline]
+    pub fn errors(&self) -> u32 {
+        self.errors
+    }
+
+    /// Returns the number of deals won by an entrant, where shared wins are
+    /// split between the winners.
+    pub fn deals_won(&self, entrant: usize) -> f64 {
+        self.deal_scores
+            .iter()
+            .map(|scores| {
+                let best = scores.iter().max().unwrap();
+                match scores[entrant] == *best {
+                    true => 1.0 / scores.iter().filter(|&score| score == best).count() as f64,
+                    false => 0.0,
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the average margin of an entrant over the average of the
+    /// other entrants on the same deal, together with its standard error.
+    pub fn margin(&self, entrant: usize) -> (f64, f64) {
+        let others = (self.names.len() - 1) as f64;
+        let margins = self
+            .deal_scores
+            .iter()
+            .map(|scores| {
+                let rest = scores.iter().sum::<i32>() - scores[entrant];
+                scores[entrant] as f64 - rest as f64 / others
+            })
+            .collect::<Vec<_>>();
+
+        let deals = margins.len() as f64;
+        if deals == 0.0 {
+            return (0.0, 0.0);
+        }
+        let mean = margins.iter().sum::<f64>() / deals;
+        let variance = margins.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / deals;
+        (mean, (variance / deals).sqrt())
+    }
+}
+
+impl Display for DuplicateResults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .names
+            .iter()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0)
+            .max(8);
+
+        writeln!(
+            f,
+            "{} complete deals, {} skipped",
+            self.deal_scores.len(),
+            self.errors
+        )?;
+        writeln!(
+            f,
+            "{:width$} {:>10} {:>10} {:>8} {:>8}",
+            "entrant", "deals won", "avg/deal", "margin", "stderr"
+        )?;
+        for (entrant, name) in self.names.iter().enumerate() {
+            let deals = self.deal_scores.len().max(1) as f64;
+            let average = self
+                .deal_scores
+                .iter()
+                .map(|scores| scores[entrant] as f64)
+                .sum::<f64>()
+                / deals;
+            let (margin, error) = self.margin(entrant);
+            writeln!(
+                f,
+                "{:width$} {:>10.1} {:>10.2} {:>+8.2} {:>8.2}",
+                name,
+                self.deals_won(entrant),
+                average,
+                margin,
+                error
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A match where every deal is replayed once for each rotation of the
+/// entrants across the seats, such that every entrant plays each deal from
+/// every seat. Comparing the summed scores per deal removes most of the luck
+/// of the deck order.
+pub struct DuplicateMatch {
+    entrants: Vec<(String, BehaviorFactory)>,
+    deals: usize,
+    seed: u64,
+}
+
+impl Default for DuplicateMatch {
+    fn default() -> Self {
+        Self {
+            entrants: Vec::new(),
+            deals: 100,
+            seed: 0,
+        }
+    }
+}
+
+impl DuplicateMatch {
+    /// Adds an entrant, where `factory` creates a new behavior for every game.
+    /// Every entrant takes part in every game.
+    pub fn add_entrant(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn() -> Box<dyn PlayerBehavior> + 'static,
+    ) {
+        self.entrants.push((name.into(), Box::new(factory)));
+    }
+
+    pub fn with_deals(mut self, deals: usize) -> Self {
+        self.deals = deals;
+        self
+    }
+
+    /// Sets the seed of the first deal, where each following deal increments
+    /// the seed by one.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Plays every rotation of every deal. Deals where any game ended with an
+    /// error are skipped. This function will return an error if there are
+    /// fewer than two or more than four entrants.
+    pub fn run(&self) -> Result<DuplicateResults> {
+        let players = self.entrants.len();
+        if players > 4 {
+            return Err(GemError::ReachedPlayerLimit);
+        }
+        if players < 2 {
+            return Err(GemError::TooFewPlayers);
+        }
+
+        let mut results = DuplicateResults {
+            names: self.entrants.iter().map(|(name, _)| name.clone()).collect(),
+            deal_scores: Vec::new(),
+            errors: 0,
+        };
+
+        for deal in 0..self.deals {
+            let mut deck = Card::gem_deck();
+            deck.shuffle(&mut StdRng::seed_from_u64(self.seed + deal as u64));
+
+            let mut scores = vec![0; players];
+            let mut complete = true;
+            for rotation in 0..players {
+                let mut seats = (0..players).collect::<Vec<_>>();
+                seats.rotate_left(rotation);
+
+                let mut setup = GameSetup::default();
+                for &entrant in &seats {
+                    setup.insert_player((self.entrants[entrant].1)())?;
+                }
+                setup.set_deck(deck.clone());
+
+                match setup.finish()?.run() {
+                    Ok(game_scores) => seats
+                        .iter()
+                        .enumerate()
+                        .for_each(|(seat, &entrant)| scores[entrant] += game_scores.get(seat)),
+                    Err(_) => {
+                        complete = false;
+                        break;
+                    }
+                }
+            }
+
+            match complete {
+                true => results.deal_scores.push(scores),
+                false => results.errors += 1,
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::tournament::testing::{Eager, Passive};
+
+    #[test]
+    fn every_rotation_replays_the_same_deal() {
+        let stacks = Rc::new(RefCell::new(Vec::new()));
+        let mut duplicate = DuplicateMatch::default().with_deals(3).with_seed(11);
+        for name in ["a", "b", "c"] {
+            let stacks = stacks.clone();
+            duplicate.add_entrant(name, move || {
+                Box::new(Passive {
+                    stacks: stacks.clone(),
+                    ..Default::default()
+                })
+            });
+        }
+        let results = duplicate.run().unwrap();
+        assert_eq!((results.deal_scores().len(), results.errors()), (3, 0));
+
+        // every game of a deal is identical, as the seats are interchangeable
+        let stacks = stacks.take();
+        let games = stacks.chunks(stacks.len() / 9).collect::<Vec<_>>();
+        for deal in games.chunks(3) {
+            assert!(deal.iter().all(|game| game == &deal[0]));
+        }
+        assert_ne!(games[0], games[3]);
+    }
+
+    #[test]
+    fn deal_scores_cancel_out_the_seats() {
+        let mut duplicate = DuplicateMatch::default().with_deals(4);
+        duplicate.add_entrant("first", || Box::new(Eager));
+        duplicate.add_entrant("second", || Box::new(Eager));
+        let results = duplicate.run().unwrap();
+
+        // identical entrants playing every deal from both seats score the same
+        assert_eq!(results.deal_scores().len(), 4);
+        for scores in results.deal_scores() {
+            assert_eq!(scores[0], scores[1]);
+            assert!(scores[0] > 0);
+        }
+        assert_eq!(results.deals_won(0), 2.0);
+        assert_eq!(results.margin(0), (0.0, 0.0));
+    }
+}
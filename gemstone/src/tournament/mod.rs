@@ -0,0 +1,13 @@
+mod duplicate;
+mod rating;
+mod round_robin;
+mod simulation;
+mod sprt;
+#[cfg(test)]
+mod testing;
+
+pub use duplicate::{DuplicateMatch, DuplicateResults};
+pub use rating::{BotId, RatingEntry, RatingLedger};
+pub use round_robin::{BehaviorFactory, EntrantStats, GameRecord, Tournament, TournamentResults};
+pub use simulation::{Simulation, SimulationStats};
+pub use sprt::{Sprt, SprtMatch, SprtOutcome, SprtReport};
@@ -0,0 +1,382 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+use crate::{
+    errors::{GemError, Result},
+    game::GameScores,
+};
+
+use super::TournamentResults;
+
+const HEADER: &str = "# gem rating ledger v1";
+
+/// Identifies a single version of a bot in the [`RatingLedger`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BotId {
+    name: String,
+    version: String,
+}
+
+impl BotId {
+    /// Creates an id from a name and a version. This function will return an
+    /// error if either contains a tab or a line break, which would break the
+    /// saved ledger, or if the name contains an `@`, which would make the id
+    /// read back as a different name and version.
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Result<Self> {
+        let (name, version) = (name.into(), version.into());
+        let breaks_line = |text: &str| text.contains(['\t', '\n', '\r']);
+        if breaks_line(&name) || breaks_line(&version) || name.contains('@') {
+            return Err(GemError::InvalidBotId(format!("{name}@{version}")));
+        }
+        Ok(Self { name, version })
+    }
+
+    /// Parses an id written as `name@version`, where the version is empty if
+    /// it is left out.
+    pub fn parse(id: &str) -> Result<Self> {
+        match id.split_once('@') {
+            Some((name, version)) => Self::new(name, version),
+            None => Self::new(id, ""),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+impl Display for BotId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.version.is_empty() {
+            true => write!(f, "{}", self.name),
+            false => write!(f, "{}@{}", self.name, self.version),
+        }
+    }
+}
+
+/// The rating of a single bot version.
+#[derive(Clone, Debug)]
+pub struct RatingEntry {
+    pub rating: f64,
+    pub games: u32,
+    /// The rating after each game, in the order the games were recorded.
+    pub history: Vec<f64>,
+}
+
+impl Default for RatingEntry {
+    fn default() -> Self {
+        Self {
+            rating: RatingLedger::INITIAL_RATING,
+            games: 0,
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Keeps multiplayer Elo ratings of bot versions across tournaments.
+///
+/// A game between `n` players is treated as every pair of players playing a
+/// match, which is won by the player with the higher score. The rating change
+/// of each player is the sum of its pairwise changes divided by `n - 1`.
+#[derive(Clone, Debug)]
+pub struct RatingLedger {
+    entries: BTreeMap<BotId, RatingEntry>,
+    k_factor: f64,
+}
+
+impl Default for RatingLedger {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            k_factor: 32.0,
+        }
+    }
+}
+
+impl RatingLedger {
+    pub const INITIAL_RATING: f64 = 1500.0;
+
+    pub fn with_k_factor(mut self, k_factor: f64) -> Self {
+        self.k_factor = k_factor;
+        self
+    }
+
+    /// Returns the rating of a bot, or the initial rating if it is unknown.
+    pub fn rating(&self, id: &BotId) -> f64 {
+        self.entries
+            .get(id)
+            .map_or(Self::INITIAL_RATING, |entry| entry.rating)
+    }
+
+    #[inline]
+    pub fn entry(&self, id: &BotId) -> Option<&RatingEntry> {
+        self.entries.get(id)
+    }
+
+    /// Returns the rating history of a bot, which is empty if it is unknown.
+    pub fn history(&self, id: &BotId) -> &[f64] {
+        self.entries
+            .get(id)
+            .map_or(&[], |entry| entry.history.as_slice())
+    }
+
+    /// Returns every bot sorted from highest to lowest rating.
+    pub fn leaderboard(&self) -> Vec<(&BotId, &RatingEntry)> {
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_by(|(_, a), (_, b)| b.rating.total_cmp(&a.rating));
+        entries
+    }
+
+    /// Updates the ratings from the final scores of a single game, where
+    /// `seats` holds the bot sitting at each seat. Games with fewer than two
+    /// seats are ignored. A bot sitting at several seats receives the sum of
+    /// the changes of its seats, and the game is counted once.
+    pub fn record_game(&mut self, seats: &[BotId], scores: &GameScores) {
+        if seats.len() < 2 {
+            return;
+        }
+        let ratings = seats.iter().map(|id| self.rating(id)).collect::<Vec<_>>();
+        let opponents = (seats.len() - 1) as f64;
+
+        let mut changes = BTreeMap::<&BotId, f64>::new();
+        for (seat, id) in seats.iter().enumerate() {
+            let change = (0..seats.len())
+                .filter(|&other| other != seat)
+                .map(|other| {
+                    let expected =
+                        1.0 / (1.0 + 10_f64.powf((ratings[other] - ratings[seat]) / 400.0));
+                    let actual = match scores.get(seat).cmp(&scores.get(other)) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+                    actual - expected
+                })
+                .sum::<f64>();
+            *changes.entry(id).or_default() += self.k_factor * change / opponents;
+        }
+
+        for (id, change) in changes {
+            let entry = self.entries.entry(id.clone()).or_default();
+            entry.rating += change;
+            entry.games += 1;
+            entry.history.push(entry.rating);
+        }
+    }
+
+    /// Updates the ratings from every finished game of a tournament, where
+    /// `entrants` holds the id of each entrant in the order they were added.
+    pub fn record_tournament(&mut self, entrants: &[BotId], results: &TournamentResults) {
+        for game in results.games() {
+            if let Some(scores) = &game.scores {
+                let seats = game
+                    .seats
+                    .iter()
+                    .map(|&entrant| entrants[entrant].clone())
+                    .collect::<Vec<_>>();
+                self.record_game(&seats, scores);
+            }
+        }
+    }
+}
+
+//
+// Persistence
+//
+
+impl RatingLedger {
+    /// Loads a ledger from a file, or returns an empty ledger if the file
+    /// does not exist.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid rating line: {line}"),
+            )
+        };
+
+        let mut ledger = Self::default();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let fields = line.split('\t').collect::<Vec<_>>();
+            if fields.len() != 5 {
+                return Err(invalid(&line));
+            }
+            let entry = RatingEntry {
+                rating: fields[2].parse().map_err(|_| invalid(&line))?,
+                games: fields[3].parse().map_err(|_| invalid(&line))?,
+                history: fields[4]
+                    .split(',')
+                    .filter(|rating| !rating.is_empty())
+                    .map(|rating| rating.parse().map_err(|_| invalid(&line)))
+                    .collect::<io::Result<_>>()?,
+            };
+            let id = BotId::new(fields[0], fields[1]).map_err(|_| invalid(&line))?;
+            ledger.entries.insert(id, entry);
+        }
+        Ok(ledger)
+    }
+
+    /// Saves the ledger as tab-separated text, with one bot per line. Ratings
+    /// are written at full precision, such that loading and saving again does
+    /// not change them.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "{HEADER}")?;
+        for (id, entry) in &self.entries {
+            let history = entry
+                .history
+                .iter()
+                .map(|rating| rating.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}",
+                id.name, id.version, entry.rating, entry.games, history
+            )?;
+        }
+        file.flush()
+    }
+}
+
+impl Display for RatingLedger {
+    /// Displays the leaderboard, including the rating change over the last
+    /// ten games of each bot.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let leaderboard = self.leaderboard();
+        let width = leaderboard
+            .iter()
+            .map(|(id, _)| id.to_string().len())
+            .max()
+            .unwrap_or(0)
+            .max(3);
+
+        writeln!(
+            f,
+            "{:>4} {:width$} {:>8} {:>6} {:>7}",
+            "rank", "bot", "rating", "games", "last10"
+        )?;
+        for (rank, (id, entry)) in leaderboard.iter().enumerate() {
+            let before = match entry.history.len() {
+                len if len > 10 => entry.history[len - 11],
+                _ => Self::INITIAL_RATING,
+            };
+            writeln!(
+                f,
+                "{:>4} {:width$} {:>8.1} {:>6} {:>+7.1}",
+                rank + 1,
+                id.to_string(),
+                entry.rating,
+                entry.games,
+                entry.rating - before,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger(ratings: &[(&str, f64)]) -> RatingLedger {
+        let mut ledger = RatingLedger::default();
+        for &(name, rating) in ratings {
+            ledger.entries.insert(
+                BotId::parse(name).unwrap(),
+                RatingEntry {
+                    rating,
+                    ..Default::default()
+                },
+            );
+        }
+        ledger
+    }
+
+    #[test]
+    fn games_with_fewer_than_two_seats_are_ignored() {
+        let mut ledger = ledger(&[("a", 1600.0)]);
+        ledger.record_game(&[], &GameScores::default());
+        ledger.record_game(&[BotId::parse("a").unwrap()], &GameScores::default());
+        let entry = ledger.entry(&BotId::parse("a").unwrap()).unwrap();
+        assert_eq!((entry.rating, entry.games), (1600.0, 0));
+    }
+
+    #[test]
+    fn bot_at_several_seats_counts_the_game_once() {
+        let mut ledger = ledger(&[("a", 1600.0), ("b", 1400.0)]);
+        let (a, b) = (BotId::parse("a").unwrap(), BotId::parse("b").unwrap());
+        ledger.record_game(&[a.clone(), a.clone(), b.clone()], &GameScores::default());
+
+        let entry = ledger.entry(&a).unwrap();
+        assert_eq!((entry.games, entry.history.len()), (1, 1));
+        // both seats drew against the weaker bot, and lose the same amount
+        let loss = 1600.0 - entry.rating;
+        assert!(loss > 0.0);
+        assert!((ledger.rating(&b) - 1400.0 - loss).abs() < 1e-9);
+    }
+
+    #[test]
+    fn saving_and_loading_keeps_full_precision() {
+        let mut ledger = ledger(&[("a@1", 1600.0), ("b", 1400.0)]);
+        for _ in 0..3 {
+            ledger.record_game(
+                &[BotId::parse("a@1").unwrap(), BotId::parse("b").unwrap()],
+                &Default::default(),
+            );
+        }
+        let path = std::env::temp_dir().join(format!("gem-ratings-{}.tsv", std::process::id()));
+        ledger.save(&path).unwrap();
+        let loaded = RatingLedger::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        for (id, entry) in &ledger.entries {
+            let other = loaded.entry(id).unwrap();
+            assert_eq!(entry.rating, other.rating);
+            assert_eq!(entry.history, other.history);
+        }
+    }
+
+    #[test]
+    fn ids_breaking_the_ledger_are_rejected() {
+        let id = BotId::parse("heuristic@v2@rc1").unwrap();
+        assert_eq!((id.name(), id.version()), ("heuristic", "v2@rc1"));
+        assert_eq!(BotId::parse(&id.to_string()).unwrap(), id);
+
+        for (name, version) in [("a\tb", ""), ("a", "1\n"), ("a\r", "1"), ("a@b", "1")] {
+            assert!(matches!(
+                BotId::new(name, version),
+                Err(GemError::InvalidBotId(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn ledgers_with_broken_ids_are_rejected() {
+        let path = std::env::temp_dir().join(format!("gem-broken-{}.tsv", std::process::id()));
+        fs::write(&path, format!("{HEADER}\na@b\t1\t1500\t0\t\n")).unwrap();
+        let loaded = RatingLedger::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
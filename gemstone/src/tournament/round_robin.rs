@@ -0,0 +1,444 @@
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    errors::{GemError, Result},
+    game::{CardChoice, Game, GameInfo, GameScores},
+    player::PlayerBehavior,
+    BidValue,
+};
+
+/// A function creating a new instance of a [`PlayerBehavior`] for every game.
+pub type BehaviorFactory = Box<dyn Fn() -> Box<dyn PlayerBehavior>>;
+
+/// The accumulated time spent by a single seat making decisions.
+#[derive(Default)]
+struct Clock {
+    decisions: u64,
+    elapsed: Duration,
+}
+
+/// Wraps a [`PlayerBehavior`] and measures the time spent on each decision.
+struct Timed {
+    inner: Box<dyn PlayerBehavior>,
+    clock: Rc<RefCell<Clock>>,
+}
+
+impl Timed {
+    fn measure<T>(&mut self, decide: impl FnOnce(&mut dyn PlayerBehavior) -> T) -> T {
+        let start = Instant::now();
+        let decision = decide(self.inner.as_mut());
+        let mut clock = self.clock.borrow_mut();
+        clock.decisions += 1;
+        clock.elapsed += start.elapsed();
+        decision
+    }
+}
+
+impl PlayerBehavior for Timed {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        self.measure(|inner| inner.bid(info))
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        self.measure(|inner| inner.pick_card(info))
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        self.measure(|inner| inner.reinvest(info))
+    }
+
+    fn resigned(&self) -> bool {
+        self.inner.resigned()
+    }
+}
+
+/// The statistics of a single entrant across a tournament.
+#[derive(Clone, Debug, Default)]
+pub struct EntrantStats {
+    pub name: String,
+    /// The number of games played, including games ending in an error.
+    pub games: u32,
+    /// The number of games played which finished without errors.
+    pub finished: u32,
+    /// The number of games won, where shared wins are split between winners.
+    pub wins: f64,
+    /// The sum of all final scores, only counting games without errors.
+    pub total_score: i64,
+    /// The number of games which ended because of an illegal move by this entrant.
+    pub illegal_moves: u32,
+    /// The number of games which ended because this entrant resigned.
+    pub resignations: u32,
+    pub decisions: u64,
+    pub thinking: Duration,
+}
+
+impl EntrantStats {
+    /// Returns the average final score of games finished without errors.
+    pub fn average_score(&self) -> f64 {
+        match self.finished {
+            0 => 0.0,
+            _ => self.total_score as f64 / self.finished as f64,
+        }
+    }
+
+    /// Returns the fraction of games won.
+    pub fn win_rate(&self) -> f64 {
+        match self.games {
+            0 => 0.0,
+            _ => self.wins / self.games as f64,
+        }
+    }
+
+    /// Returns the average time spent on a single decision.
+    pub fn time_per_decision(&self) -> Duration {
+        match self.decisions {
+            0 => Duration::ZERO,
+            _ => self.thinking.div_f64(self.decisions as f64),
+        }
+    }
+}
+
+/// The outcome of a single tournament game.
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    /// The entrant index of each seat, in seating order.
+    pub seats: Vec<usize>,
+    /// The final scores, or `None` if the game ended with an error.
+    pub scores: Option<GameScores>,
+}
+
+/// The entrant ending a game with an error, by the seat it played from.
+enum Fault {
+    Illegal(usize),
+    Resigned(usize),
+}
+
+/// The results of a [`Tournament`], which displays as a score table followed
+/// by a cross-table of head-to-head results.
+#[derive(Clone, Debug)]
+pub struct TournamentResults {
+    stats: Vec<EntrantStats>,
+    /// Head-to-head points of the row entrant against the column entrant,
+    /// where beating an opponent in a game gives one point and a tie half.
+    head_to_head: Vec<Vec<f64>>,
+    /// The number of finished games the row and column entrant played together.
+    meetings: Vec<Vec<u32>>,
+    games: Vec<GameRecord>,
+}
+
+impl TournamentResults {
+    fn new(names: Vec<String>) -> Self {
+        let n = names.len();
+        Self {
+            stats: names
+                .into_iter()
+                .map(|name| EntrantStats {
+                    name,
+                    ..Default::default()
+                })
+                .collect(),
+            head_to_head: vec![vec![0.0; n]; n],
+            meetings: vec![vec![0; n]; n],
+            games: Vec::new(),
+        }
+    }
+
+    /// Returns the statistics of each entrant, in the order they were added.
+    #[inline]
+    pub fn stats(&self) -> &[EntrantStats] {
+        &self.stats
+    }
+
+    /// Returns the record of every game played, in the order they were played.
+    #[inline]
+    pub fn games(&self) -> &[GameRecord] {
+        &self.games
+    }
+
+    /// Returns the fraction of head-to-head points the first entrant scored
+    /// against the second entrant, if they ever met.
+    pub fn head_to_head(&self, entrant: usize, opponent: usize) -> Option<f64> {
+        match self.meetings[entrant][opponent] {
+            0 => None,
+            meetings => Some(self.head_to_head[entrant][opponent] / meetings as f64),
+        }
+    }
+
+    fn record(&mut self, record: GameRecord, fault: Option<Fault>) {
+        for &entrant in &record.seats {
+            self.stats[entrant].games += 1;
+        }
+        match fault {
+            Some(Fault::Illegal(seat)) => self.stats[record.seats[seat]].illegal_moves += 1,
+            Some(Fault::Resigned(seat)) => self.stats[record.seats[seat]].resignations += 1,
+            None => {}
+        }
+
+        if let Some(scores) = record.scores {
+            let seats = &record.seats;
+            let best = (0..seats.len()).map(|seat| scores.get(seat)).max().unwrap();
+            let winners = (0..seats.len())
+                .filter(|&seat| scores.get(seat) == best)
+                .count();
+
+            for (seat, &entrant) in seats.iter().enumerate() {
+                let stats = &mut self.stats[entrant];
+                stats.finished += 1;
+                stats.total_score += scores.get(seat) as i64;
+                if scores.get(seat) == best {
+                    stats.wins += 1.0 / winners as f64;
+                }
+                for (other_seat, &other) in seats.iter().enumerate() {
+                    if other_seat == seat {
+                        continue;
+                    }
+                    self.meetings[entrant][other] += 1;
+                    self.head_to_head[entrant][other] +=
+                        match scores.get(seat).cmp(&scores.get(other_seat)) {
+                            std::cmp::Ordering::Greater => 1.0,
+                            std::cmp::Ordering::Equal => 0.5,
+                            std::cmp::Ordering::Less => 0.0,
+                        };
+                }
+            }
+        }
+        self.games.push(record);
+    }
+}
+
+impl Display for TournamentResults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .stats
+            .iter()
+            .map(|stats| stats.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(8);
+
+        writeln!(
+            f,
+            "{:width$} {:>6} {:>7} {:>6} {:>9} {:>8} {:>8} {:>12}",
+            "entrant", "games", "wins", "win%", "avg score", "illegal", "resigned", "ms/decision"
+        )?;
+        for stats in &self.stats {
+            writeln!(
+                f,
+                "{:width$} {:>6} {:>7.1} {:>6.1} {:>9.2} {:>8} {:>8} {:>12.3}",
+                stats.name,
+                stats.games,
+                stats.wins,
+                stats.win_rate() * 100.0,
+                stats.average_score(),
+                stats.illegal_moves,
+                stats.resignations,
+                stats.time_per_decision().as_secs_f64() * 1000.0,
+            )?;
+        }
+
+        writeln!(f)?;
+        write!(f, "{:width$}", "")?;
+        for stats in &self.stats {
+            write!(f, " {:>width$}", stats.name)?;
+        }
+        writeln!(f)?;
+        for (entrant, stats) in self.stats.iter().enumerate() {
+            write!(f, "{:width$}", stats.name)?;
+            for opponent in 0..self.stats.len() {
+                match self.head_to_head(entrant, opponent) {
+                    Some(points) => write!(f, " {:>width$.1}", points * 100.0)?,
+                    None => write!(f, " {:>width$}", "-")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A round-robin tournament between a number of named entrants. Every
+/// combination of entrants is seated in every rotation, such that each
+/// entrant plays equally often from each seat. Every game is dealt from its
+/// own seed, which is the tournament seed plus the index of the game, such
+/// that a tournament can be replayed with the same seed.
+pub struct Tournament {
+    entrants: Vec<(String, BehaviorFactory)>,
+    players_per_game: usize,
+    games_per_seating: usize,
+    seed: u64,
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Self {
+            entrants: Vec::new(),
+            players_per_game: 2,
+            games_per_seating: 1,
+            seed: 0,
+        }
+    }
+}
+
+impl Tournament {
+    /// Adds an entrant, where `factory` creates a new behavior for every game.
+    pub fn add_entrant(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn() -> Box<dyn PlayerBehavior> + 'static,
+    ) {
+        self.entrants.push((name.into(), Box::new(factory)));
+    }
+
+    /// Sets the number of players in each game, in range `[2..5)`.
+    pub fn with_players_per_game(mut self, players: usize) -> Self {
+        self.players_per_game = players;
+        self
+    }
+
+    /// Sets the number of games played for each seating.
+    pub fn with_games_per_seating(mut self, games: usize) -> Self {
+        self.games_per_seating = games;
+        self
+    }
+
+    /// Sets the seed of the first game, where each following game increments
+    /// the seed by one.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Plays every game of the tournament. Games ending in an error are
+    /// counted as an illegal move by the player whose decision caused it,
+    /// or as a resignation if the player resigned.
+    /// This function will return an error if there are fewer entrants than
+    /// players per game, or if the number of players per game is invalid.
+    pub fn run(&self) -> Result<TournamentResults> {
+        if self.players_per_game > 4 {
+            return Err(GemError::ReachedPlayerLimit);
+        }
+        if self.players_per_game < 2 || self.entrants.len() < self.players_per_game {
+            return Err(GemError::TooFewPlayers);
+        }
+
+        let names = self.entrants.iter().map(|(name, _)| name.clone()).collect();
+        let mut results = TournamentResults::new(names);
+
+        for group in combinations(self.entrants.len(), self.players_per_game) {
+            for rotation in 0..group.len() {
+                let mut seats = group.clone();
+                seats.rotate_left(rotation);
+                for _ in 0..self.games_per_seating {
+                    let seed = self.seed.wrapping_add(results.games.len() as u64);
+                    self.play(&seats, seed, &mut results);
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    fn play(&self, seats: &[usize], seed: u64, results: &mut TournamentResults) {
+        let clocks = seats
+            .iter()
+            .map(|_| Rc::new(RefCell::new(Clock::default())))
+            .collect::<Vec<_>>();
+        let behaviors = seats
+            .iter()
+            .zip(&clocks)
+            .map(|(&entrant, clock)| {
+                Box::new(Timed {
+                    inner: (self.entrants[entrant].1)(),
+                    clock: clock.clone(),
+                }) as Box<dyn PlayerBehavior>
+            })
+            .collect();
+
+        let mut game = Game::with_rng(behaviors, &mut StdRng::seed_from_u64(seed));
+        let (scores, fault) = match game.run() {
+            Ok(scores) => (Some(scores), None),
+            Err(GemError::PlayerResigned(seat)) => (None, Some(Fault::Resigned(seat))),
+            Err(_) => (None, Some(Fault::Illegal(game.info_ref().current_player()))),
+        };
+
+        for (&entrant, clock) in seats.iter().zip(&clocks) {
+            let clock = clock.borrow();
+            results.stats[entrant].decisions += clock.decisions;
+            results.stats[entrant].thinking += clock.elapsed;
+        }
+        let record = GameRecord {
+            seats: seats.to_vec(),
+            scores,
+        };
+        results.record(record, fault);
+    }
+}
+
+/// Returns every combination of `k` indices in range `[0..n)`.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    (k - 1..n)
+        .flat_map(|last| {
+            combinations(last, k - 1).into_iter().map(move |mut group| {
+                group.push(last);
+                group
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tournament::testing::Passive;
+
+    /// Returns every stack seen in a tournament between three passive
+    /// entrants, where the last one resigns if `resigning` is set.
+    fn run(seed: u64, resigning: bool) -> (TournamentResults, Vec<String>) {
+        let stacks = Rc::new(RefCell::new(Vec::new()));
+        let mut tournament = Tournament::default()
+            .with_players_per_game(2)
+            .with_games_per_seating(3)
+            .with_seed(seed);
+        for (name, resign) in [("a", false), ("b", false), ("c", resigning)] {
+            let stacks = stacks.clone();
+            tournament.add_entrant(name, move || {
+                Box::new(Passive {
+                    resign,
+                    stacks: stacks.clone(),
+                })
+            });
+        }
+        let results = tournament.run().unwrap();
+        let stacks = stacks.take();
+        (results, stacks)
+    }
+
+    #[test]
+    fn games_are_dealt_from_the_tournament_seed() {
+        let (results, stacks) = run(5, false);
+        assert_eq!(results.games().len(), 3 * 2 * 3);
+        assert!(results.games().iter().all(|game| game.scores.is_some()));
+        assert_eq!(stacks, run(5, false).1);
+        assert_ne!(stacks, run(6, false).1);
+    }
+
+    #[test]
+    fn resignations_are_not_illegal_moves() {
+        let (results, _) = run(0, true);
+        let stats = results.stats();
+        assert_eq!(stats[2].games, 12);
+        assert_eq!(stats[2].resignations, 12);
+        assert!(stats.iter().all(|stats| stats.illegal_moves == 0));
+        assert_eq!(stats[0].resignations + stats[1].resignations, 0);
+        assert_eq!(stats[0].finished, 6);
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    fmt::Display,
+    num::NonZeroUsize,
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    errors::{GemError, Result},
+    game::Game,
+    player::{SendBehavior, SendBehaviorFactory},
+};
+
+/// The merged statistics of a [`Simulation`], indexed by seat.
+#[derive(Clone, Debug, Default)]
+pub struct SimulationStats {
+    /// The number of games played, including games ending in an error.
+    pub games: u32,
+    pub errors: u32,
+    /// The number of games won from each seat, where shared wins are split.
+    pub wins: Vec<f64>,
+    pub total_scores: Vec<i64>,
+    /// The sum of the squared scores, used for the standard deviation.
+    pub squared_scores: Vec<i64>,
+    /// The wall-clock time spent running the simulation.
+    pub elapsed: Duration,
+}
+
+impl SimulationStats {
+    fn new(seats: usize) -> Self {
+        Self {
+            wins: vec![0.0; seats],
+            total_scores: vec![0; seats],
+            squared_scores: vec![0; seats],
+            ..Default::default()
+        }
+    }
+
+    /// Adds the statistics of another simulation with the same seats.
+    pub fn merge(&mut self, other: &SimulationStats) {
+        self.games += other.games;
+        self.errors += other.errors;
+        for seat in 0..self.wins.len() {
+            self.wins[seat] += other.wins[seat];
+            self.total_scores[seat] += other.total_scores[seat];
+            self.squared_scores[seat] += other.squared_scores[seat];
+        }
+    }
+
+    /// Returns the average score and its standard deviation from a seat.
+    pub fn score(&self, seat: usize) -> (f64, f64) {
+        let finished = (self.games - self.errors) as f64;
+        if finished == 0.0 {
+            return (0.0, 0.0);
+        }
+        let mean = self.total_scores[seat] as f64 / finished;
+        let variance = self.squared_scores[seat] as f64 / finished - mean * mean;
+        (mean, variance.max(0.0).sqrt())
+    }
+}
+
+impl Display for SimulationStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} games ({} errors) in {:.2}s",
+            self.games,
+            self.errors,
+            self.elapsed.as_secs_f64()
+        )?;
+        writeln!(
+            f,
+            "{:>4} {:>8} {:>6} {:>9} {:>7}",
+            "seat", "wins", "win%", "avg score", "stddev"
+        )?;
+        for seat in 0..self.wins.len() {
+            let (mean, deviation) = self.score(seat);
+            writeln!(
+                f,
+                "{:>4} {:>8.1} {:>6.1} {:>9.2} {:>7.2}",
+                seat,
+                self.wins[seat],
+                self.wins[seat] / self.games.max(1) as f64 * 100.0,
+                mean,
+                deviation
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays a large number of independent games across multiple threads.
+///
+/// Every game is dealt from its own seed, which is the base seed plus the
+/// index of the game. The results therefore do not depend on the number of
+/// threads, as long as the behaviors themselves are deterministic.
+pub struct Simulation {
+    seats: Vec<SendBehaviorFactory>,
+    games: usize,
+    threads: usize,
+    seed: u64,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self {
+            seats: Vec::new(),
+            games: 1000,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            seed: 0,
+        }
+    }
+}
+
+impl Simulation {
+    /// Adds a seat, where `factory` creates a new behavior for every game.
+    pub fn add_seat(&mut self, factory: impl Fn() -> Box<SendBehavior> + Send + Sync + 'static) {
+        self.seats.push(Box::new(factory));
+    }
+
+    pub fn with_games(mut self, games: usize) -> Self {
+        self.games = games;
+        self
+    }
+
+    /// Sets the number of worker threads, which defaults to the number of
+    /// available cores.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Plays every game and merges the statistics of all threads. This
+    /// function will return an error if the number of seats is invalid.
+    pub fn run(&self) -> Result<SimulationStats> {
+        if self.seats.len() > 4 {
+            return Err(GemError::ReachedPlayerLimit);
+        }
+        if self.seats.len() < 2 {
+            return Err(GemError::TooFewPlayers);
+        }
+
+        let start = Instant::now();
+        let mut stats = SimulationStats::new(self.seats.len());
+        thread::scope(|scope| {
+            let workers = (0..self.threads)
+                .map(|worker| scope.spawn(move || self.work(worker)))
+                .collect::<Vec<_>>();
+            for worker in workers {
+                stats.merge(&worker.join().expect("simulation worker panicked"));
+            }
+        });
+        stats.elapsed = start.elapsed();
+        Ok(stats)
+    }
+
+    /// Plays every game whose index belongs to the given worker.
+    fn work(&self, worker: usize) -> SimulationStats {
+        let mut stats = SimulationStats::new(self.seats.len());
+        for idx in (worker..self.games).step_by(self.threads) {
+            let behaviors = self.seats.iter().map(|factory| factory()).collect();
+            let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(idx as u64));
+            let mut game = Game::<SendBehavior>::with_rng(behaviors, &mut rng);
+
+            stats.games += 1;
+            let scores = match game.run() {
+                Ok(scores) => scores,
+                Err(_) => {
+                    stats.errors += 1;
+                    continue;
+                }
+            };
+
+            let best = (0..self.seats.len())
+                .map(|seat| scores.get(seat))
+                .max()
+                .unwrap();
+            let winners = (0..self.seats.len())
+                .filter(|&seat| scores.get(seat) == best)
+                .count();
+            for seat in 0..self.seats.len() {
+                let score = scores.get(seat) as i64;
+                stats.total_scores[seat] += score;
+                stats.squared_scores[seat] += score * score;
+                if scores.get(seat) == best {
+                    stats.wins[seat] += 1.0 / winners as f64;
+                }
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tournament::testing::Eager;
+
+    fn simulate(threads: usize) -> SimulationStats {
+        let mut simulation = Simulation::default()
+            .with_games(25)
+            .with_threads(threads)
+            .with_seed(9);
+        for _ in 0..3 {
+            simulation.add_seat(|| Box::new(Eager));
+        }
+        simulation.run().unwrap()
+    }
+
+    #[test]
+    fn results_do_not_depend_on_the_number_of_threads() {
+        let single = simulate(1);
+        assert_eq!((single.games, single.errors), (25, 0));
+        assert_eq!(single.wins.iter().sum::<f64>(), 25.0);
+
+        for threads in [2, 4, 32] {
+            let parallel = simulate(threads);
+            assert_eq!((parallel.games, parallel.errors), (25, 0));
+            assert_eq!(parallel.wins, single.wins);
+            assert_eq!(parallel.total_scores, single.total_scores);
+            assert_eq!(parallel.squared_scores, single.squared_scores);
+        }
+    }
+}
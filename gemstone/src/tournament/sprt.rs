@@ -0,0 +1,330 @@
+use std::fmt::Display;
+
+use crate::{
+    errors::{GemError, Result},
+    game::GameSetup,
+    player::PlayerBehavior,
+};
+
+use super::BehaviorFactory;
+
+/// The state of a sequential probability ratio test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SprtOutcome {
+    /// The Elo gain of the candidate is accepted, meaning it is at least `elo1`.
+    Accepted,
+    /// The Elo gain of the candidate is rejected, meaning it is at most `elo0`.
+    Rejected,
+    /// Not enough games have been played to reach a decision.
+    Inconclusive,
+}
+
+/// A sequential probability ratio test between the hypotheses that the Elo
+/// difference of a candidate is `elo0` versus `elo1`, using the normal
+/// approximation of the generalised SPRT.
+#[derive(Clone, Copy, Debug)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    /// The probability of accepting the gain when it is at most `elo0`.
+    pub alpha: f64,
+    /// The probability of rejecting the gain when it is at least `elo1`.
+    pub beta: f64,
+}
+
+impl Default for Sprt {
+    fn default() -> Self {
+        Self {
+            elo0: 0.0,
+            elo1: 10.0,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+}
+
+impl Sprt {
+    /// Returns the lower and upper bounds of the log-likelihood ratio.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Returns the log-likelihood ratio given the results of the candidate.
+    pub fn llr(&self, wins: u32, draws: u32, losses: u32) -> f64 {
+        let games = (wins + draws + losses) as f64;
+        if games == 0.0 {
+            return 0.0;
+        }
+        let score = (wins as f64 + draws as f64 * 0.5) / games;
+        let variance = (wins as f64 * (1.0 - score).powi(2)
+            + draws as f64 * (0.5 - score).powi(2)
+            + losses as f64 * score.powi(2))
+            / games;
+        // avoid dividing by zero when every game had the same result
+        let variance = variance.max(0.01);
+        let score0 = elo_to_score(self.elo0);
+        let score1 = elo_to_score(self.elo1);
+        games * (score1 - score0) * (2.0 * score - score0 - score1) / (2.0 * variance)
+    }
+
+    /// Returns the outcome of the test given the log-likelihood ratio.
+    pub fn outcome(&self, llr: f64) -> SprtOutcome {
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtOutcome::Accepted
+        } else if llr <= lower {
+            SprtOutcome::Rejected
+        } else {
+            SprtOutcome::Inconclusive
+        }
+    }
+}
+
+fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10_f64.powf(-elo / 400.0))
+}
+
+fn score_to_elo(score: f64) -> f64 {
+    let score = score.clamp(1e-6, 1.0 - 1e-6);
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// The result of a [`SprtMatch`].
+#[derive(Clone, Copy, Debug)]
+pub struct SprtReport {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// The number of games ending with an error, which are not counted.
+    pub errors: u32,
+    pub llr: f64,
+    pub bounds: (f64, f64),
+    pub outcome: SprtOutcome,
+}
+
+impl SprtReport {
+    /// Returns the number of games counted by the test.
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Returns the estimated Elo difference of the candidate over the
+    /// baseline, together with the 95% confidence margin.
+    pub fn elo(&self) -> (f64, f64) {
+        let games = self.games() as f64;
+        if games == 0.0 {
+            return (0.0, f64::INFINITY);
+        }
+        let score = (self.wins as f64 + self.draws as f64 * 0.5) / games;
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / games;
+        let margin = 1.96 * (variance / games).sqrt();
+        let elo = score_to_elo(score);
+        let upper = score_to_elo(score + margin);
+        let lower = score_to_elo(score - margin);
+        (elo, (upper - lower) / 2.0)
+    }
+}
+
+impl Display for SprtReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (elo, margin) = self.elo();
+        writeln!(
+            f,
+            "games {} (W/D/L {}/{}/{}, {} errors)",
+            self.games(),
+            self.wins,
+            self.draws,
+            self.losses,
+            self.errors
+        )?;
+        writeln!(
+            f,
+            "llr {:.2} [{:.2}, {:.2}]",
+            self.llr, self.bounds.0, self.bounds.1
+        )?;
+        writeln!(f, "elo {elo:+.1} +/- {margin:.1}")?;
+        write!(f, "outcome {:?}", self.outcome)
+    }
+}
+
+/// Plays a candidate [`PlayerBehavior`] against a baseline until a [`Sprt`]
+/// reaches a decision.
+///
+/// Games are played in pairs using the same seed, where the candidate and
+/// the baseline swap seats in the second game. The seats of the pair rotate
+/// from one pair to the next, and any remaining seats in games with three or
+/// four players are taken by the fillers. A game counts as a win for the
+/// candidate if it scores more than the baseline.
+pub struct SprtMatch {
+    candidate: BehaviorFactory,
+    baseline: BehaviorFactory,
+    fillers: Vec<BehaviorFactory>,
+    players_per_game: usize,
+    sprt: Sprt,
+    max_games: u32,
+    seed: u64,
+}
+
+impl SprtMatch {
+    pub fn new(
+        candidate: impl Fn() -> Box<dyn PlayerBehavior> + 'static,
+        baseline: impl Fn() -> Box<dyn PlayerBehavior> + 'static,
+    ) -> Self {
+        Self {
+            candidate: Box::new(candidate),
+            baseline: Box::new(baseline),
+            fillers: Vec::new(),
+            players_per_game: 2,
+            sprt: Sprt::default(),
+            max_games: 10_000,
+            seed: 0,
+        }
+    }
+
+    /// Adds a filler behavior, which is seated in the remaining seats of
+    /// games with more than two players.
+    pub fn add_filler(&mut self, filler: impl Fn() -> Box<dyn PlayerBehavior> + 'static) {
+        self.fillers.push(Box::new(filler));
+    }
+
+    /// Sets the number of players in each game, in range `[2..5)`.
+    pub fn with_players_per_game(mut self, players: usize) -> Self {
+        self.players_per_game = players;
+        self
+    }
+
+    pub fn with_sprt(mut self, sprt: Sprt) -> Self {
+        self.sprt = sprt;
+        self
+    }
+
+    /// Sets the number of games after which the match is stopped, even if
+    /// the test is inconclusive. An odd limit ends the match after the first
+    /// game of the last pair.
+    pub fn with_max_games(mut self, games: u32) -> Self {
+        self.max_games = games;
+        self
+    }
+
+    /// Sets the seed of the first game pair, where each following pair
+    /// increments the seed by one.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Plays game pairs until the test reaches a decision or the maximum
+    /// number of games is reached. This function will return an error if the
+    /// number of players per game is invalid, or if more than two players
+    /// are requested without any fillers.
+    pub fn run(&self) -> Result<SprtReport> {
+        if self.players_per_game > 4 {
+            return Err(GemError::ReachedPlayerLimit);
+        }
+        if self.players_per_game < 2 || (self.players_per_game > 2 && self.fillers.is_empty()) {
+            return Err(GemError::TooFewPlayers);
+        }
+
+        let mut report = SprtReport {
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            errors: 0,
+            llr: 0.0,
+            bounds: self.sprt.bounds(),
+            outcome: SprtOutcome::Inconclusive,
+        };
+
+        let mut pair = 0;
+        while report.games() + report.errors < self.max_games {
+            let seed = self.seed + pair as u64;
+            let seat = pair % self.players_per_game;
+            let other = (seat + 1) % self.players_per_game;
+            for (candidate, baseline) in [(seat, other), (other, seat)] {
+                if report.games() + report.errors >= self.max_games {
+                    break;
+                }
+                match self.play(seed, candidate, baseline)? {
+                    Some((ours, theirs)) => match ours.cmp(&theirs) {
+                        std::cmp::Ordering::Greater => report.wins += 1,
+                        std::cmp::Ordering::Equal => report.draws += 1,
+                        std::cmp::Ordering::Less => report.losses += 1,
+                    },
+                    None => report.errors += 1,
+                }
+            }
+            pair += 1;
+
+            report.llr = self.sprt.llr(report.wins, report.draws, report.losses);
+            report.outcome = self.sprt.outcome(report.llr);
+            if report.outcome != SprtOutcome::Inconclusive {
+                break;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Plays a single game, returning the scores of the candidate and the
+    /// baseline, or `None` if the game ended with an error.
+    fn play(&self, seed: u64, candidate: usize, baseline: usize) -> Result<Option<(i32, i32)>> {
+        let mut setup = GameSetup::default();
+        let mut fillers = self.fillers.iter().cycle();
+        for seat in 0..self.players_per_game {
+            let behavior = match seat {
+                _ if seat == candidate => (self.candidate)(),
+                _ if seat == baseline => (self.baseline)(),
+                _ => (fillers.next().unwrap())(),
+            };
+            setup.insert_player(behavior)?;
+        }
+        setup.set_seed(seed);
+
+        let mut game = setup.finish()?;
+        Ok(game
+            .run()
+            .ok()
+            .map(|scores| (scores.get(candidate), scores.get(baseline))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tournament::testing::{Eager, Passive};
+
+    fn eager() -> Box<dyn PlayerBehavior> {
+        Box::new(Eager)
+    }
+
+    fn passive() -> Box<dyn PlayerBehavior> {
+        Box::new(Passive::default())
+    }
+
+    #[test]
+    fn outcomes_follow_the_results_of_a_fixed_seed() {
+        let report = SprtMatch::new(eager, passive).with_seed(3).run().unwrap();
+        assert_eq!(report.outcome, SprtOutcome::Accepted);
+        assert_eq!(report.errors, 0);
+        assert!(report.llr >= report.bounds.1);
+
+        let report = SprtMatch::new(passive, eager).with_seed(3).run().unwrap();
+        assert_eq!(report.outcome, SprtOutcome::Rejected);
+        assert!(report.llr <= report.bounds.0);
+    }
+
+    #[test]
+    fn odd_game_limits_are_not_exceeded() {
+        let report = SprtMatch::new(passive, passive)
+            .with_max_games(7)
+            .run()
+            .unwrap();
+        assert_eq!(report.outcome, SprtOutcome::Inconclusive);
+        assert_eq!((report.games(), report.draws), (7, 7));
+    }
+}
@@ -0,0 +1,76 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    analysis::{MinimalOverpay, PaymentOptimizer},
+    game::{CardChoice, CardIterator, GameInfo},
+    player::PlayerBehavior,
+    BidValue,
+};
+
+/// Outbids the highest bid whenever it can afford to, buys the first card
+/// of the stack with the cheapest payment and spends its coins flipping
+/// as many leveraged gems as they cover.
+pub struct Eager;
+
+impl PlayerBehavior for Eager {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        match info.my_inventory().iter().capital() > info.highest_bid() {
+            true => info.highest_bid() + 1,
+            false => -1,
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        let payment = PaymentOptimizer::new(info.inventory_at(info.highest_bidder()))
+            .best(info.highest_bid(), &MinimalOverpay)
+            .map_or(CardChoice::NONE, |payment| payment.choice);
+        (0, payment)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        let inventory = info.my_inventory();
+        let mut budget = 0;
+        let mut flipped = Vec::new();
+        for (idx, card) in inventory.iter().enumerate() {
+            if card.is_coin() && !card.is_leveraged() {
+                budget += card.scalar_value();
+                flipped.push(idx);
+            }
+        }
+        for (idx, card) in inventory.iter().enumerate() {
+            if !card.is_coin() && card.is_leveraged() && budget + card.scalar_value() >= 0 {
+                budget += card.scalar_value();
+                flipped.push(idx);
+            }
+        }
+        CardChoice::new(&flipped)
+    }
+}
+
+/// Never bids, takes the first card of the stack for free and never
+/// reinvests, such that it never scores. Logs every stack it bids on, and
+/// resigns at its first decision if `resign` is set.
+#[derive(Default)]
+pub struct Passive {
+    pub resign: bool,
+    pub stacks: Rc<RefCell<Vec<String>>>,
+}
+
+impl PlayerBehavior for Passive {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        self.stacks.borrow_mut().push(format!("{:?}", info.stack()));
+        -1
+    }
+
+    fn pick_card(&mut self, _info: &GameInfo) -> (usize, CardChoice) {
+        (0, CardChoice::NONE)
+    }
+
+    fn reinvest(&mut self, _info: &GameInfo) -> CardChoice {
+        CardChoice::NONE
+    }
+
+    fn resigned(&self) -> bool {
+        self.resign
+    }
+}
@@ -0,0 +1,164 @@
+use std::result::Result;
+
+use gemstone::*;
+
+/// Splits an answer into words, separated by whitespace or commas.
+pub fn tokenize(input: &str) -> Vec<&str> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/// Returns whether the first word is the keyword of a card-code answer, such
+/// as `buy` or `flip`. Words too long to be card codes or indices are
+/// rejected, with a suggestion if they resemble a keyword.
+pub fn keyword(tokens: &[&str], keywords: &[&str]) -> Result<Option<&'static str>, String> {
+    let first = match tokens.first() {
+        Some(first) => first.to_lowercase(),
+        None => return Ok(None),
+    };
+    if let Some(&keyword) = KEYWORDS.iter().find(|&&keyword| keyword == first) {
+        return match keywords.contains(&keyword) {
+            true => Ok(Some(keyword)),
+            false => Err(format!("`{keyword}` cannot be used here")),
+        };
+    }
+    if first.len() > 2 && first.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!(
+            "unknown command `{first}`{}",
+            did_you_mean(&first, keywords.iter().copied())
+        ));
+    }
+    Ok(None)
+}
+
+pub(crate) const KEYWORDS: [&str; 3] = ["buy", "pay", "flip"];
+
+/// How [`resolve_cards`] matches card codes to leveraged cards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeverageRule {
+    /// Codes match cards regardless of leverage, as on the stack.
+    Ignore,
+    /// Codes prefixed by `!` match leveraged cards, and any other code
+    /// matches non-leveraged cards.
+    Prefix,
+    /// Codes only match non-leveraged cards, as when paying.
+    NonLeveraged,
+}
+
+/// Resolves card codes, such as `SE`, `2` or `!D`, to distinct indices of
+/// `cards`.
+pub fn resolve_cards(
+    tokens: &[&str],
+    cards: &[Card],
+    rule: LeverageRule,
+) -> Result<Vec<usize>, String> {
+    let mut indices = Vec::new();
+    for &token in tokens {
+        let (leveraged, code) = match token.strip_prefix('!') {
+            Some(code) => (true, code.to_uppercase()),
+            None => (false, token.to_uppercase()),
+        };
+        if leveraged && rule == LeverageRule::NonLeveraged {
+            return Err(format!("`{token}` is leveraged and cannot be used"));
+        }
+        let card = GemNotation::parse_card(&code).ok_or_else(|| {
+            format!(
+                "`{token}` is not a card code{}",
+                did_you_mean(&code, codes(cards, rule))
+            )
+        })?;
+
+        let matches = |idx: usize, leverage: bool| {
+            same_kind(cards[idx], card)
+                && (rule == LeverageRule::Ignore || cards[idx].is_leveraged() == leverage)
+                && !indices.contains(&idx)
+        };
+        if let Some(idx) = (0..cards.len()).find(|&idx| matches(idx, leveraged)) {
+            indices.push(idx);
+            continue;
+        }
+        // the card exists, but with the other leverage state
+        if (0..cards.len()).any(|idx| matches(idx, !leveraged)) {
+            return Err(match (leveraged, rule) {
+                (false, LeverageRule::NonLeveraged) => format!("`{code}` is already leveraged"),
+                (false, _) => {
+                    format!("there is no non-leveraged `{code}`, did you mean `!{code}`?")
+                }
+                (true, _) => format!("there is no leveraged `{code}`, did you mean `{code}`?"),
+            });
+        }
+        let other = match indices.iter().any(|&idx| same_kind(cards[idx], card)) {
+            true => "other ",
+            false => "",
+        };
+        return Err(format!(
+            "there is no {other}`{code}`{}",
+            did_you_mean(&code, codes(cards, rule))
+        ));
+    }
+    Ok(indices)
+}
+
+fn same_kind(a: Card, b: Card) -> bool {
+    match (a.is_coin(), b.is_coin()) {
+        (true, true) => a.value() == b.value(),
+        (false, false) => a.archtype() == b.archtype(),
+        _ => false,
+    }
+}
+
+/// Returns the codes of the cards, with `!` before leveraged cards unless
+/// leverage is ignored.
+fn codes(cards: &[Card], rule: LeverageRule) -> impl Iterator<Item = String> + '_ {
+    cards.iter().map(move |&card| {
+        let prefix = match card.is_leveraged() && rule != LeverageRule::Ignore {
+            true => "!",
+            false => "",
+        };
+        format!("{prefix}{}", GemNotation::format_card(card))
+    })
+}
+
+/// Returns a suggestion for the candidate closest to the word, if any
+/// candidate is close enough to be a likely typo.
+pub(crate) fn did_you_mean<S: AsRef<str>>(
+    word: &str,
+    candidates: impl Iterator<Item = S>,
+) -> String {
+    let word = word.to_uppercase();
+    candidates
+        .map(|candidate| {
+            let distance = distance(&word, &candidate.as_ref().to_uppercase());
+            (distance, candidate.as_ref().to_string())
+        })
+        .filter(|&(distance, _)| distance <= 2.min(word.len()))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| format!(", did you mean `{candidate}`?"))
+        .unwrap_or_default()
+}
+
+/// Returns the number of single character edits between two words, where
+/// swapping two neighbouring characters counts as a single edit.
+fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    table[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            table[i][j] = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                table[i][j] = table[i][j].min(table[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    table[a.len()][b.len()]
+}
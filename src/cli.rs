@@ -0,0 +1,828 @@
+use std::{
+    fmt::Display, fs, io, path::PathBuf, process::ExitCode, rc::Rc, result::Result, str::FromStr,
+    sync::Arc, time::Duration,
+};
+
+use behaviors::CfrTrainer;
+use gemstone::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    human_player::HumanBehavior,
+    repl::Repl,
+    tui::{TuiBehavior, TuiSession},
+};
+
+pub const USAGE: &str = "\
+usage: gemai <command> [options]
+
+commands:
+  play      play a single game
+              --seat <spec>     add a seat, repeated for 2-4 seats, where the
+                                spec is a behavior name with optional
+                                parameters, such as `heuristic:bid_rate=0.7`
+              --seats <names>   comma-separated behavior names without
+                                parameters (default: human,greedy), where
+                                `tui` seats share a full-screen interface
+              --seed <n>        seed used to deal the cards
+              --record <file>   write every position to a transcript file,
+                                annotated with the narration if `--narrate`
+              --practice        allow human seats to take back decisions,
+                                which needs a `human` seat
+              --narrate         describe every step of a game between bots,
+                                which cannot have `human` or `tui` seats
+  simulate  play a batch of games between bots and print statistics
+              --seat <spec>     add a seat, as for `play`
+              --seats <names>   comma-separated behavior names (default: greedy,heuristic)
+              --games <n>       number of games (default: 100)
+              --seed <n>        seed of the first game (default: 0)
+              --threads <n>     number of threads (default: all cores)
+  analyze <notation>
+            print an analysis of the position
+  replay <record>
+            print every position and comment of a recorded game
+  check <transcript>
+            check that every position of a transcript follows from the
+            previous position by a single step, where positions separated
+            by an empty line are not compared
+  engine <spec>
+            serve a behavior over the Gem Engine Interface on standard
+            input and output, such that another build can play it as an
+            `engine` seat, such as `engine:cmd=gemai engine greedy`, with
+            the optional parameters `timeout` in milliseconds, `restarts`
+            and `option.<name>`
+  help      print this message and the available behaviors
+
+exit codes: 0 on success, 1 if a game ended with an error, 2 on invalid
+usage, and 3 on invalid input or if a file could not be read or written.";
+
+/// A subcommand parsed from the command-line arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Play {
+        seats: Vec<BehaviorSpec>,
+        seed: Option<u64>,
+        record: Option<PathBuf>,
+        practice: bool,
+        narrate: bool,
+    },
+    Simulate {
+        seats: Vec<BehaviorSpec>,
+        games: usize,
+        seed: u64,
+        threads: Option<usize>,
+    },
+    Analyze {
+        notation: String,
+    },
+    Replay {
+        record: PathBuf,
+    },
+    Check {
+        transcript: PathBuf,
+    },
+    Engine {
+        spec: BehaviorSpec,
+    },
+    TrainCfr {
+        output: PathBuf,
+        players: usize,
+        iterations: usize,
+        seed: u64,
+    },
+    Help,
+}
+
+/// The reason a command failed, which determines the exit code.
+#[derive(Debug)]
+pub enum Failure {
+    /// A game ended with an error.
+    Game(GemError),
+    /// The command-line arguments were invalid.
+    Usage(String),
+    /// A notation or record was invalid, or a file could not be accessed.
+    Input(String),
+}
+
+impl Failure {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Game(_) => ExitCode::from(1),
+            Self::Usage(_) => ExitCode::from(2),
+            Self::Input(_) => ExitCode::from(3),
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Game(err) => write!(f, "the game ended with an error: {err}"),
+            Self::Usage(reason) | Self::Input(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(err: io::Error) -> Self {
+        Self::Input(err.to_string())
+    }
+}
+
+//
+// Parsing
+//
+
+impl Command {
+    /// Parses the arguments following the name of the binary.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, Failure> {
+        let mut args = args.into_iter();
+        let name = match args.next() {
+            Some(name) => name,
+            None => return Ok(Self::Help),
+        };
+        let (mut options, positional) = Self::split_options(args)?;
+
+        let command = match name.as_str() {
+            "play" => Self::Play {
+                seats: Self::parse_seats(&mut options, "human,greedy")?,
+                seed: options
+                    .take("seed")
+                    .map(|seed| parse_value("seed", &seed))
+                    .transpose()?,
+                record: options.take("record").map(PathBuf::from),
+                practice: options.take("practice").is_some(),
+                narrate: options.take("narrate").is_some(),
+            },
+            "simulate" => Self::Simulate {
+                seats: Self::parse_seats(&mut options, "greedy,heuristic")?,
+                games: options
+                    .take("games")
+                    .map_or(Ok(100), |games| parse_value("games", &games))?,
+                seed: options
+                    .take("seed")
+                    .map_or(Ok(0), |seed| parse_value("seed", &seed))?,
+                threads: options
+                    .take("threads")
+                    .map(|threads| parse_value("threads", &threads))
+                    .transpose()?,
+            },
+            "analyze" => Self::Analyze {
+                notation: Self::single(&name, &positional)?,
+            },
+            "replay" => Self::Replay {
+                record: PathBuf::from(Self::single(&name, &positional)?),
+            },
+            "check" => Self::Check {
+                transcript: PathBuf::from(Self::single(&name, &positional)?),
+            },
+            "engine" => Self::Engine {
+                spec: Self::single(&name, &positional)?
+                    .parse()
+                    .map_err(spec_error)?,
+            },
+            "train-cfr" => Self::TrainCfr {
+                output: PathBuf::from(Self::single(&name, &positional)?),
+                players: options
+                    .take("players")
+                    .map_or(Ok(3), |players| parse_value("players", &players))?,
+                iterations: options
+                    .take("iterations")
+                    .map_or(Ok(100_000), |iterations| {
+                        parse_value("iterations", &iterations)
+                    })?,
+                seed: options
+                    .take("seed")
+                    .map_or(Ok(0), |seed| parse_value("seed", &seed))?,
+            },
+            "help" | "--help" | "-h" => Self::Help,
+            _ => return Err(Failure::Usage(format!("unknown command `{name}`"))),
+        };
+
+        if let Self::Play {
+            seats,
+            practice,
+            narrate,
+            ..
+        } = &command
+        {
+            let has_seat =
+                |names: &[&str]| seats.iter().any(|spec| names.contains(&spec.name.as_str()));
+            if *practice && !has_seat(&["human"]) {
+                return Err(Failure::Usage("`--practice` needs a `human` seat".into()));
+            }
+            if *narrate && has_seat(&["human", "tui"]) {
+                return Err(Failure::Usage(
+                    "`--narrate` cannot be used with `human` or `tui` seats".into(),
+                ));
+            }
+        }
+        if let Some((option, _)) = options.0.first() {
+            return Err(Failure::Usage(format!(
+                "unknown option `--{option}` for `{name}`"
+            )));
+        }
+        if !matches!(
+            command,
+            Self::Analyze { .. }
+                | Self::Replay { .. }
+                | Self::Check { .. }
+                | Self::Engine { .. }
+                | Self::TrainCfr { .. }
+        ) && !positional.is_empty()
+        {
+            return Err(Failure::Usage(format!(
+                "unexpected argument `{}`",
+                positional[0]
+            )));
+        }
+        Ok(command)
+    }
+
+    /// Splits the arguments into options, written as `--name value` or
+    /// `--name=value`, and positional arguments. Flags take no value.
+    fn split_options(
+        mut args: impl Iterator<Item = String>,
+    ) -> Result<(Options, Vec<String>), Failure> {
+        let mut options = Vec::new();
+        let mut positional = Vec::new();
+        while let Some(arg) = args.next() {
+            let name = match arg.strip_prefix("--") {
+                Some(name) => name,
+                None => {
+                    positional.push(arg);
+                    continue;
+                }
+            };
+            let (name, value) = match name.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None if FLAGS.contains(&name) => (name.to_string(), String::new()),
+                None => match args.next() {
+                    Some(value) => (name.to_string(), value),
+                    None => return Err(Failure::Usage(format!("missing value for `--{name}`"))),
+                },
+            };
+            options.push((name, value));
+        }
+        Ok((Options(options), positional))
+    }
+
+    fn parse_seats(options: &mut Options, default: &str) -> Result<Vec<BehaviorSpec>, Failure> {
+        let mut seats = Vec::new();
+        while let Some(spec) = options.take("seat") {
+            seats.push(spec);
+        }
+        if let Some(names) = options.take("seats") {
+            seats.extend(names.split(',').map(str::to_string));
+        }
+        if seats.is_empty() {
+            seats.extend(default.split(',').map(str::to_string));
+        }
+        let seats = seats
+            .iter()
+            .map(|spec| spec.parse().map_err(spec_error))
+            .collect::<Result<Vec<BehaviorSpec>, Failure>>()?;
+        if !(2..=4).contains(&seats.len()) {
+            return Err(Failure::Usage(format!(
+                "expected 2-4 seats, got {}",
+                seats.len()
+            )));
+        }
+        Ok(seats)
+    }
+
+    fn single(command: &str, positional: &[String]) -> Result<String, Failure> {
+        match positional {
+            [arg] => Ok(arg.clone()),
+            _ => Err(Failure::Usage(format!(
+                "`{command}` expects exactly one argument"
+            ))),
+        }
+    }
+}
+
+/// The options given without a value.
+const FLAGS: [&str; 2] = ["practice", "narrate"];
+
+/// The options given to a command, which are removed as they are read.
+struct Options(Vec<(String, String)>);
+
+impl Options {
+    fn take(&mut self, name: &str) -> Option<String> {
+        let idx = self.0.iter().position(|(other, _)| other == name)?;
+        Some(self.0.remove(idx).1)
+    }
+}
+
+fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, Failure> {
+    value
+        .parse()
+        .map_err(|_| Failure::Usage(format!("invalid value `{value}` for `--{name}`")))
+}
+
+fn spec_error(err: GemError) -> Failure {
+    Failure::Usage(err.to_string())
+}
+
+/// Returns a registry of every bot and the human player.
+pub fn registry() -> BehaviorRegistry {
+    let mut registry = BehaviorRegistry::default();
+    registry.register("human", "plays from the terminal", |params| {
+        let name = params.get("name", "Human".to_string())?;
+        Ok(Box::new(HumanBehavior::new(&name)))
+    });
+    // every seat at the table shares the terminal
+    let session = TuiSession::new();
+    registry.register(
+        "tui",
+        "plays on a full-screen terminal interface",
+        move |params| {
+            let name = params.get("name", "Player".to_string())?;
+            Ok(Box::new(TuiBehavior::new(&name, session.clone())))
+        },
+    );
+    registry.register(
+        "engine",
+        "plays by an engine process, see `engine`",
+        |params| {
+            let cmd = params.get("cmd", String::new())?;
+            let mut words = cmd.split_whitespace();
+            let program = words.next().ok_or_else(|| {
+                GemError::InvalidBehaviorSpec("missing `cmd` for `engine`".to_string())
+            })?;
+            let mut engine = EngineBehavior::new(program)
+                .with_args(words)
+                .with_timeout(Duration::from_millis(params.get("timeout", 5000)?))
+                .with_restarts(params.get("restarts", 1)?)
+                .with_failure_report(|err| eprintln!("{err}"));
+            for (name, value) in params.take_prefixed("option.") {
+                engine = engine.with_option(name, value);
+            }
+            Ok(Box::new(engine.start()?))
+        },
+    );
+    behaviors::register(&mut registry);
+    registry
+}
+
+//
+// Commands
+//
+
+impl Command {
+    pub fn run(self) -> Result<(), Failure> {
+        match self {
+            Self::Play {
+                seats,
+                seed,
+                record,
+                practice,
+                narrate,
+            } => match seats.iter().any(|spec| spec.name == "human") {
+                true => play_interactive(seats, seed, record, practice),
+                false => play(seats, seed, record, narrate),
+            },
+            Self::Simulate {
+                seats,
+                games,
+                seed,
+                threads,
+            } => simulate(seats, games, seed, threads),
+            Self::Analyze { notation } => {
+                let info = parse_notation(&notation)?;
+                analyze(&info);
+                Ok(())
+            }
+            Self::Replay { record } => replay(record),
+            Self::Check { transcript } => check(transcript),
+            Self::Engine { spec } => serve(spec),
+            Self::TrainCfr {
+                output,
+                players,
+                iterations,
+                seed,
+            } => train_cfr(output, players, iterations, seed),
+            Self::Help => {
+                println!("{USAGE}\n\nbehaviors:");
+                for (name, description) in registry().entries() {
+                    println!("  {name:<10}{description}");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn play(
+    mut seats: Vec<BehaviorSpec>,
+    seed: Option<u64>,
+    record: Option<PathBuf>,
+    narrate: bool,
+) -> Result<(), Failure> {
+    let registry = registry();
+    let mut setup = GameSetup::default();
+    name_seats(&mut seats);
+    for spec in &seats {
+        let behavior = registry.create_from(spec).map_err(spec_error)?;
+        setup.insert_player(behavior).map_err(Failure::Game)?;
+    }
+    if let Some(seed) = seed {
+        setup.set_seed(seed);
+    }
+    let mut game = setup.finish().map_err(Failure::Game)?;
+
+    // seats are narrated by position, as several may play the same behavior
+    let narrator = Narrator::new();
+    if narrate {
+        narrator
+            .narrate_start(game.info_ref())
+            .iter()
+            .for_each(|line| println!("{line}"));
+    }
+    let mut transcript = Transcript::new();
+    transcript.push_position(game.info_ref());
+    let result = loop {
+        let before = game.info_ref().clone();
+        let result = game.step();
+        if game.info_ref().history().len() > before.history().len() {
+            if narrate {
+                for sentence in narrator.narrate(&before, game.info_ref()) {
+                    println!("{sentence}");
+                    transcript.push_comment(&sentence);
+                }
+            }
+            transcript.push_position(game.info_ref());
+        }
+        match result {
+            Ok(Some(scores)) => break Ok(scores),
+            Ok(None) => {}
+            Err(err) => break Err(err),
+        }
+    };
+    if let Some(path) = record {
+        fs::write(path, transcript.to_string())?;
+    }
+    // the terminal of `tui` seats is restored once the registry and the
+    // behaviors are dropped
+    let info = game.info_ref().clone();
+    drop(game);
+    drop(registry);
+
+    let scores = match result {
+        Ok(scores) => scores,
+        Err(GemError::PlayerResigned(seat)) => {
+            println!("seat {seat} ({}) resigned", seats[seat].name);
+            return Ok(());
+        }
+        Err(err) => {
+            post_mortem(&info, seats.iter().map(|spec| spec.name.clone()));
+            return Err(Failure::Game(err));
+        }
+    };
+
+    println!("final scores:");
+    for (seat, spec) in seats.iter().enumerate() {
+        println!("  seat {seat} ({}): {}", spec.name, scores.get(seat));
+    }
+    Ok(())
+}
+
+/// Plays a game with human seats in the [`Repl`], where humans may inspect
+/// the game between decisions.
+fn play_interactive(
+    mut seats: Vec<BehaviorSpec>,
+    seed: Option<u64>,
+    record: Option<PathBuf>,
+    practice: bool,
+) -> Result<(), Failure> {
+    // the prompt and the full-screen interface would both read from stdin
+    if seats.iter().any(|spec| spec.name == "tui") {
+        return Err(Failure::Usage(
+            "cannot play with both human and tui seats".into(),
+        ));
+    }
+    let registry = Rc::new(registry());
+    let mut repl = Repl::new().with_practice(practice);
+    name_seats(&mut seats);
+    for spec in &seats {
+        // every spec is created once, such that its parameters are checked
+        registry.create_from(spec).map_err(spec_error)?;
+        let result = match spec.name.as_str() {
+            "human" => {
+                // every human seat was given a name above
+                let (_, name) = spec.params.iter().find(|(key, _)| key == "name").unwrap();
+                repl.add_human(name)
+            }
+            _ => {
+                let (registry, spec) = (registry.clone(), spec.clone());
+                repl.add_bot(&spec.name.clone(), move || -> Box<dyn PlayerBehavior> {
+                    registry.create_from(&spec).unwrap()
+                })
+            }
+        };
+        result.map_err(Failure::Game)?;
+    }
+    if let Some(seed) = seed {
+        repl.set_seed(seed);
+    }
+
+    let result = repl.run();
+    if let (Err(_), Some(info)) = (&result, repl.positions().last()) {
+        post_mortem(info, seats.iter().map(|spec| spec.name.clone()));
+    }
+    if let Some(path) = record {
+        let mut transcript = Transcript::new();
+        repl.positions()
+            .iter()
+            .for_each(|info| transcript.push_position(info));
+        fs::write(path, transcript.to_string())?;
+    }
+    result.map(|_| ()).map_err(Failure::Game)
+}
+
+/// Names every `human` and `tui` seat without a name after its position,
+/// such as `Player 2`.
+fn name_seats(seats: &mut [BehaviorSpec]) {
+    for (seat, spec) in seats.iter_mut().enumerate() {
+        let interactive = spec.name == "human" || spec.name == "tui";
+        if interactive && spec.params.iter().all(|(key, _)| key != "name") {
+            spec.params
+                .push(("name".to_string(), format!("Player {}", seat + 1)));
+        }
+    }
+}
+
+/// Prints the position a game failed in to stderr.
+fn post_mortem(info: &GameInfo, names: impl Iterator<Item = String>) {
+    let renderer = BoardRenderer::for_stream(&io::stderr()).with_names(names);
+    eprintln!("{}", renderer.render(info));
+    eprintln!("{}", GemNotation::from_info(info));
+}
+
+fn simulate(
+    seats: Vec<BehaviorSpec>,
+    games: usize,
+    seed: u64,
+    threads: Option<usize>,
+) -> Result<(), Failure> {
+    let mut simulation = Simulation::default().with_games(games).with_seed(seed);
+    if let Some(threads) = threads {
+        simulation = simulation.with_threads(threads);
+    }
+    let registry = Arc::new(registry());
+    for spec in seats {
+        if spec.name == "human" {
+            return Err(Failure::Usage("cannot simulate games with humans".into()));
+        }
+        simulation.add_seat(registry.factory(&spec).map_err(spec_error)?);
+    }
+    let stats = simulation.run().map_err(Failure::Game)?;
+    print!("{stats}");
+    Ok(())
+}
+
+/// Serves a behavior as an engine, where the parameters of the spec become
+/// options of the engine.
+fn serve(spec: BehaviorSpec) -> Result<(), Failure> {
+    if matches!(spec.name.as_str(), "human" | "tui") {
+        return Err(Failure::Usage(format!(
+            "cannot serve `{}` as an engine",
+            spec.name
+        )));
+    }
+    let registry = registry();
+    registry.create_from(&spec).map_err(spec_error)?;
+    let mut server = EngineServer::new(spec.name.clone(), {
+        let name = spec.name.clone();
+        move |options| {
+            registry.create_from(&BehaviorSpec {
+                name: name.clone(),
+                params: options.to_vec(),
+            })
+        }
+    });
+    for (name, default) in spec.params {
+        server = server.with_option(name, default);
+    }
+    server.run(io::stdin().lock(), io::stdout().lock())?;
+    Ok(())
+}
+
+/// Trains a strategy table for the `cfr` behavior and saves it.
+fn train_cfr(output: PathBuf, players: usize, iterations: usize, seed: u64) -> Result<(), Failure> {
+    if !(2..=4).contains(&players) {
+        return Err(Failure::Usage(format!(
+            "expected 2-4 players, got {players}"
+        )));
+    }
+    let mut trainer = CfrTrainer::new(players);
+    trainer.train(iterations, &mut StdRng::seed_from_u64(seed));
+    let table = trainer.strategy();
+    table
+        .save(&output)
+        .map_err(|err| Failure::Input(format!("cannot write {}: {err}", output.display())))?;
+    println!(
+        "{}: {} information sets after {iterations} iterations",
+        output.display(),
+        table.len()
+    );
+    Ok(())
+}
+
+fn replay(path: PathBuf) -> Result<(), Failure> {
+    let transcript = read_transcript(&path)?;
+    let mut positions = 0;
+    for line in transcript.lines() {
+        let notation = match line {
+            TranscriptLine::Position(notation) => notation,
+            TranscriptLine::Comment(comment) => {
+                println!("     --- {comment}");
+                continue;
+            }
+            TranscriptLine::Break => {
+                println!();
+                continue;
+            }
+        };
+        let info = notation.to_info().map_err(Failure::Game)?;
+
+        positions += 1;
+        let scores = info.scores();
+        let scores = (0..info.num_players())
+            .map(|player| scores.get(player).to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let decision = match info.next_decision() {
+            Some((player, decision)) => format!("player {player} to {decision:?}"),
+            None => "game over".to_string(),
+        };
+        println!(
+            "{positions:>3}. round {} | {decision:<24} | scores {scores} | {notation}",
+            info.round_index() + 1
+        );
+    }
+    Ok(())
+}
+
+/// Checks that every position of a transcript follows from the previous one.
+fn check(path: PathBuf) -> Result<(), Failure> {
+    let transcript = read_transcript(&path)?;
+    transcript
+        .validate()
+        .map_err(|err| Failure::Input(err.to_string()))?;
+    println!(
+        "{}: {} positions follow from each other",
+        path.display(),
+        transcript.positions().count()
+    );
+    Ok(())
+}
+
+fn read_transcript(path: &PathBuf) -> Result<Transcript, Failure> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| Failure::Input(format!("cannot open {}: {err}", path.display())))?;
+    text.parse()
+        .map_err(|err: GemError| Failure::Input(err.to_string()))
+}
+
+fn parse_notation(notation: &str) -> Result<GameInfo, Failure> {
+    notation
+        .parse::<GemNotation>()
+        .and_then(|notation| notation.to_info())
+        .map_err(|err| Failure::Input(err.to_string()))
+}
+
+fn analyze(info: &GameInfo) {
+    println!("{}", GemNotation::from_info(info));
+    println!("round {} of 6", info.round_index() + 1);
+
+    let scores = info.scores();
+    for player in 0..info.num_players() {
+        println!(
+            "  player {player}: capital {:>2}, score {:>2}",
+            info.inventory_at(player).iter().capital(),
+            scores.get(player)
+        );
+    }
+    for gem in GemType::iter() {
+        println!("  {gem:?}: {:?}", info.majorities().majority(gem));
+    }
+
+    let (player, decision) = match info.next_decision() {
+        Some(next) => next,
+        None => return,
+    };
+    println!("player {player} to {decision:?}");
+    match decision {
+        Decision::Bid | Decision::PickCard => {
+            let valuation = BidValuation::new(info);
+            for (idx, value) in valuation.values(player).iter().enumerate() {
+                println!(
+                    "  card {idx} ({}): value {:.2}",
+                    GemNotation::format_card(value.card),
+                    value.total(valuation.weights())
+                );
+            }
+            if decision == Decision::Bid {
+                println!("suggested maximum bid: {}", valuation.max_bid(player));
+            } else if let Some(card) = valuation.best_card(player) {
+                println!("suggested card: {card}");
+            }
+        }
+        Decision::Reinvest => {
+            let plans = ReinvestmentPlanner::new(info, player).plans();
+            for plan in plans.iter().take(3) {
+                println!("  {}", plan.explanation);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, Failure> {
+        Command::parse(args.split_whitespace().map(str::to_string))
+    }
+
+    fn specs(specs: &[&str]) -> Vec<BehaviorSpec> {
+        specs.iter().map(|spec| spec.parse().unwrap()).collect()
+    }
+
+    fn usage_error(args: &str) -> String {
+        match parse(args) {
+            Err(failure @ Failure::Usage(_)) => {
+                assert_eq!(failure.exit_code(), ExitCode::from(2));
+                failure.to_string()
+            }
+            other => panic!("expected a usage error for `{args}`, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn commands_are_parsed_with_their_defaults() {
+        assert_eq!(parse("").unwrap(), Command::Help);
+        assert_eq!(
+            parse("play").unwrap(),
+            Command::Play {
+                seats: specs(&["human", "greedy"]),
+                seed: None,
+                record: None,
+                practice: false,
+                narrate: false,
+            }
+        );
+        assert_eq!(
+            parse("play --seat heuristic:bid_rate=0.7 --seats=greedy,random --seed 4 --narrate")
+                .unwrap(),
+            Command::Play {
+                seats: specs(&["heuristic:bid_rate=0.7", "greedy", "random"]),
+                seed: Some(4),
+                record: None,
+                practice: false,
+                narrate: true,
+            }
+        );
+        assert_eq!(
+            parse("simulate --games 10 --threads 2").unwrap(),
+            Command::Simulate {
+                seats: specs(&["greedy", "heuristic"]),
+                games: 10,
+                seed: 0,
+                threads: Some(2),
+            }
+        );
+        assert_eq!(
+            parse("train-cfr table.txt --players 2").unwrap(),
+            Command::TrainCfr {
+                output: PathBuf::from("table.txt"),
+                players: 2,
+                iterations: 100_000,
+                seed: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_arguments_are_usage_errors() {
+        usage_error("deal");
+        usage_error("play --rules standard");
+        usage_error("play --seats greedy");
+        usage_error("play --seed");
+        usage_error("simulate --games many");
+        usage_error("analyze");
+        usage_error("replay a.txt b.txt");
+        usage_error("play extra");
+    }
+
+    #[test]
+    fn play_flags_must_fit_the_seats() {
+        assert!(usage_error("play --seats greedy,random --practice").contains("--practice"));
+        assert!(usage_error("play --seats tui,greedy --practice").contains("--practice"));
+        assert!(usage_error("play --narrate").contains("--narrate"));
+        assert!(usage_error("play --seats tui,greedy --narrate").contains("--narrate"));
+        assert!(parse("play --seats human,greedy --practice").is_ok());
+        assert!(parse("play --seats greedy,random --narrate").is_ok());
+    }
+}
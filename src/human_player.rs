@@ -1,123 +1,541 @@
+use std::{
+    io::{self, BufRead, BufReader, Stdin, Stdout, Write},
+    result::Result,
+};
+
 use gemstone::*;
 
-macro_rules! input {
-    ($expl:literal => [$parse:ty], $default:expr) => {{
-        use std::io::Write;
-        let mut stdout = std::io::stdout();
-        let stdin = std::io::stdin();
-        let mut buf = String::new();
-        let _ = write!(stdout, $expl);
-        let _ = stdout.flush();
-        let _ = stdin.read_line(&mut buf);
-        #[cfg(target_os = "windows")]
-        {
-            buf.replace("\r\n", "")
-                .split(",")
-                .flat_map(|s| s.parse::<$parse>())
-                .collect::<Vec<$parse>>()
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            buf.replace("\n", "")
-                .split(",")
-                .flat_map(|s| s.parse::<$parse>())
-                .collect::<Vec<$parse>>()
-        }
-    }};
-    ($expl:literal => $parse:ty, $default:expr) => {{
-        use std::io::Write;
-        let mut stdout = std::io::stdout();
-        let stdin = std::io::stdin();
-        let mut buf = String::new();
-        let _ = write!(stdout, $expl);
-        let _ = stdout.flush();
-        let _ = stdin.read_line(&mut buf);
-        #[cfg(target_os = "windows")]
-        {
-            buf.replace("\r\n", "")
-                .parse::<$parse>()
-                .unwrap_or($default)
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            buf.replace("\n", "").parse::<$parse>().unwrap_or($default)
-        }
-    }};
-}
+use crate::card_input::{keyword, resolve_cards, tokenize, LeverageRule};
 
-#[derive(Debug, Default)]
-pub struct HumanBehavior {
+/// A behavior asking a human for every decision, reading answers from `R` and
+/// writing the position and prompts to `W`.
+///
+/// Invalid answers are explained and asked again. Once the input ends, the
+/// player resigns and the game ends with [`GemError::PlayerResigned`].
+pub struct HumanBehavior<R = BufReader<Stdin>, W = Stdout> {
     name: String,
+    renderer: BoardRenderer,
+    reader: R,
+    writer: W,
+    resigned: bool,
 }
 
 impl HumanBehavior {
+    /// Creates a behavior playing from the terminal, drawing the board in
+    /// colour if the terminal supports it.
     pub fn new(name: &str) -> Self {
+        Self::with_io(name, BufReader::new(io::stdin()), io::stdout())
+            .with_renderer(BoardRenderer::for_stream(&io::stdout()))
+    }
+}
+
+impl<R: BufRead, W: Write> HumanBehavior<R, W> {
+    pub fn with_io(name: &str, reader: R, writer: W) -> Self {
         Self {
             name: name.to_string(),
+            renderer: BoardRenderer::plain(),
+            reader,
+            writer,
+            resigned: false,
         }
     }
 
-    fn format_cards(cards: impl AsRef<[Card]>) -> String {
-        cards
-            .as_ref()
-            .iter()
-            .enumerate()
-            .map(|(i, &card)| {
-                format!(
-                    "{i}=>{}{}",
-                    if card.is_leveraged() { "!" } else { "" },
-                    GemNotation::format_card(card)
-                )
-            })
-            .collect::<Vec<String>>()
-            .join(", ")
+    pub fn with_renderer(mut self, renderer: BoardRenderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Writes a line, ignoring errors since the input decides when the
+    /// player stops playing.
+    fn say(&mut self, line: impl AsRef<str>) {
+        let _ = writeln!(self.writer, "{}", line.as_ref());
+    }
+
+    fn show_position(&mut self, info: &GameInfo) {
+        self.say(format!("\n{} ====================", self.name));
+        self.say(self.renderer.render(info));
+        self.say(format!("{}\n", GemNotation::from_info(info)));
+    }
+
+    /// Prompts until `parse` accepts an answer. Returns `None` and resigns
+    /// once the input ends.
+    fn prompt<T>(&mut self, prompt: &str, parse: impl Fn(&str) -> Result<T, String>) -> Option<T> {
+        loop {
+            let _ = write!(self.writer, "{prompt}");
+            let _ = self.writer.flush();
+
+            let mut buf = String::new();
+            match self.reader.read_line(&mut buf) {
+                Ok(0) | Err(_) => {
+                    self.say(format!("\n{} resigned", self.name));
+                    self.resigned = true;
+                    return None;
+                }
+                Ok(_) => {}
+            }
+            match parse(buf.trim()) {
+                Ok(value) => return Some(value),
+                Err(reason) => self.say(format!("Invalid input: {reason}. Please try again.")),
+            }
+        }
     }
 }
 
-impl PlayerBehavior for HumanBehavior {
+impl<R: BufRead, W: Write> PlayerBehavior for HumanBehavior<R, W> {
     fn bid(&mut self, info: &GameInfo) -> BidValue {
-        println!("\n{} ====================", self.name);
-        println!("{}\n", GemNotation::from_info(info));
-
-        println!(
-            "Make a bid. The current highest bid is {}.",
-            info.highest_bid()
-        );
-        println!("Your capital is {}.", info.my_inventory().iter().capital());
+        self.show_position(info);
+        self.say(describe_bid(info));
 
-        input!("Enter your bid: " => BidValue, 0)
+        let capital = info.my_inventory().iter().capital();
+        self.prompt(BID_PROMPT, |input| parse_bid(input, capital))
+            .unwrap_or(0)
     }
 
     fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
-        println!("\n{} ====================", self.name);
-        println!("{}\n", GemNotation::from_info(info));
+        self.show_position(info);
+        self.say(describe_stack(info));
+
+        let card = match self.prompt(CARD_PROMPT, |input| parse_pick(input, info)) {
+            Some((card, Some(payment))) => return (card, payment),
+            Some((card, None)) => card,
+            None => return (0, CardChoice::NONE),
+        };
+
+        self.say(describe_payment(info));
+        let payment = self
+            .prompt(PAYMENT_PROMPT, |input| parse_payment(input, info))
+            .unwrap_or(CardChoice::NONE);
+        (card, payment)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        self.show_position(info);
+        self.say(describe_flip(info));
+
+        self.prompt(FLIP_PROMPT, |input| parse_flip(input, info))
+            .unwrap_or(CardChoice::NONE)
+    }
+
+    fn resigned(&self) -> bool {
+        self.resigned
+    }
+}
+
+//
+// Questions
+//
 
-        println!(
-            "Select a card. Available cards are: {}",
-            Self::format_cards(info.stack())
+pub(crate) const BID_PROMPT: &str = "Enter your bid: ";
+pub(crate) const CARD_PROMPT: &str = "Enter card (or `buy SE pay 1,3`): ";
+pub(crate) const PAYMENT_PROMPT: &str = "Enter card choices (or `pay 1,SE`): ";
+pub(crate) const FLIP_PROMPT: &str = "Enter cards (or `flip D !2`): ";
+
+/// Describes the bid asked of the current player.
+pub(crate) fn describe_bid(info: &GameInfo) -> String {
+    format!(
+        "Make a bid. The current highest bid is {}.\nYour capital is {}.",
+        info.highest_bid(),
+        info.my_inventory().iter().capital()
+    )
+}
+
+/// Describes the cards the highest bidder can pick from.
+pub(crate) fn describe_stack(info: &GameInfo) -> String {
+    format!(
+        "Select a card. Available cards are: {}",
+        format_cards(info.stack())
+    )
+}
+
+/// Describes the payment asked of the highest bidder, with a suggestion.
+pub(crate) fn describe_payment(info: &GameInfo) -> String {
+    let inventory = info.my_inventory();
+    let mut text = format!(
+        "Select payment cards (you bid {}). Your inventory is: {}",
+        info.highest_bid(),
+        format_cards(inventory)
+    );
+    let cost = ProtectMajorities::from_info(info, info.current_player());
+    if let Some(payment) = PaymentOptimizer::new(inventory).best(info.highest_bid(), &cost) {
+        text += &format!(
+            "\nSuggested payment: {} (pay {})",
+            format_choice(inventory, payment.choice),
+            format_codes(inventory, payment.choice)
         );
-        let card = input!("Enter card: " => usize, 0);
+    }
+    text
+}
 
-        println!(
-            "Select payment cards (you bid {}). Your inventory is: {}",
-            info.highest_bid(),
-            Self::format_cards(info.my_inventory())
+/// Describes the reinvestment asked of the current player, with the best
+/// suggestions.
+pub(crate) fn describe_flip(info: &GameInfo) -> String {
+    let inventory = info.my_inventory();
+    let mut text = format!(
+        "Select cards to flip. Your inventory is: {}",
+        format_cards(inventory)
+    );
+    let plans = ReinvestmentPlanner::new(info, info.current_player()).plans();
+    for plan in plans.iter().take(3) {
+        text += &format!(
+            "\nSuggestion {} (flip {}): {}",
+            format_choice(inventory, plan.choice),
+            format_codes(inventory, plan.choice),
+            plan.explanation
         );
-        let choice_indices = input!("Enter card choices: " => [usize], []);
+    }
+    text
+}
+
+fn format_cards(cards: impl AsRef<[Card]>) -> String {
+    cards
+        .as_ref()
+        .iter()
+        .enumerate()
+        .map(|(i, &card)| {
+            format!(
+                "{i}=>{}{}",
+                if card.is_leveraged() { "!" } else { "" },
+                GemNotation::format_card(card)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub(crate) fn format_choice(cards: impl AsRef<[Card]>, choice: CardChoice) -> String {
+    (0..cards.as_ref().len())
+        .filter(|&i| choice.check(i))
+        .map(|i| i.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Formats the chosen cards as codes, as accepted after `pay` or `flip`.
+pub(crate) fn format_codes(cards: impl AsRef<[Card]>, choice: CardChoice) -> String {
+    cards
+        .as_ref()
+        .iter()
+        .choose_cards(choice)
+        .map(|&card| {
+            let prefix = if card.is_leveraged() { "!" } else { "" };
+            format!("{prefix}{}", GemNotation::format_card(card))
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
 
-        (card, CardChoice::new(&choice_indices))
+//
+// Parsing
+//
+
+pub(crate) fn parse_bid(input: &str, capital: BidValue) -> Result<BidValue, String> {
+    let bid = input
+        .parse::<BidValue>()
+        .map_err(|_| format!("`{input}` is not a number"))?;
+    match (0..=capital).contains(&bid) {
+        true => Ok(bid),
+        false => Err(format!("the bid must be between 0 and {capital}")),
     }
+}
 
-    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
-        println!("\n{} ====================", self.name);
-        println!("{}\n", GemNotation::from_info(info));
+fn parse_index(input: &str, len: usize) -> Result<usize, String> {
+    let idx = input
+        .parse::<usize>()
+        .map_err(|_| format!("`{input}` is not a card index"))?;
+    match idx < len {
+        true => Ok(idx),
+        false => Err(format!("there is no card {idx}")),
+    }
+}
 
-        println!(
-            "Select cards to flip. Your inventory is: {}",
-            Self::format_cards(info.my_inventory())
-        );
+fn parse_indices(tokens: &[&str], len: usize) -> Result<Vec<usize>, String> {
+    tokens.iter().map(|idx| parse_index(idx, len)).collect()
+}
+
+/// Parses a card index, or `buy <card>` optionally followed by
+/// `pay <cards>`, in which case the payment is returned as well.
+pub(crate) fn parse_pick(
+    input: &str,
+    info: &GameInfo,
+) -> Result<(usize, Option<CardChoice>), String> {
+    let stack = info.stack().as_ref();
+    let tokens = tokenize(input);
+    if keyword(&tokens, &["buy"])?.is_none() {
+        return match tokens[..] {
+            [idx] => Ok((parse_index(idx, stack.len())?, None)),
+            _ => Err("enter a single card".to_string()),
+        };
+    }
+    let card = match tokens.get(1..2) {
+        Some(code) => resolve_cards(code, stack, LeverageRule::Ignore)?[0],
+        None => return Err("`buy` must be followed by a card".to_string()),
+    };
+    match tokens.get(2) {
+        None => Ok((card, None)),
+        Some(token) if token.eq_ignore_ascii_case("pay") => {
+            let payment = parse_payment(&tokens[2..].join(" "), info)?;
+            Ok((card, Some(payment)))
+        }
+        Some(token) => Err(format!("expected `pay` instead of `{token}`")),
+    }
+}
+
+/// Parses card indices, or `pay` followed by card codes.
+pub(crate) fn parse_payment(input: &str, info: &GameInfo) -> Result<CardChoice, String> {
+    let cards = info.my_inventory().as_ref();
+    let price = info.highest_bid();
+    let tokens = tokenize(input);
+    let indices = match keyword(&tokens, &["pay"])? {
+        Some(_) => resolve_cards(&tokens[1..], cards, LeverageRule::NonLeveraged)?,
+        None => parse_indices(&tokens, cards.len())?,
+    };
+    let choice = CardChoice::new(&indices);
+    check_payment(cards, choice, price).map(|_| choice)
+}
+
+/// Checks that the chosen cards are non-leveraged and cover the price.
+pub(crate) fn check_payment(
+    cards: &[Card],
+    choice: CardChoice,
+    price: BidValue,
+) -> Result<(), String> {
+    if let Some(idx) = (0..cards.len()).find(|&idx| choice.check(idx) && cards[idx].is_leveraged())
+    {
+        return Err(format!("card {idx} is already leveraged"));
+    }
+    let value = cards.iter().choose_cards(choice).capital();
+    match value >= price {
+        true => Ok(()),
+        false => Err(format!("the payment is worth {value}, but you bid {price}")),
+    }
+}
+
+/// Parses card indices, or `flip` followed by card codes.
+pub(crate) fn parse_flip(input: &str, info: &GameInfo) -> Result<CardChoice, String> {
+    let cards = info.my_inventory().as_ref();
+    let tokens = tokenize(input);
+    let indices = match keyword(&tokens, &["flip"])? {
+        Some(_) => resolve_cards(&tokens[1..], cards, LeverageRule::Prefix)?,
+        None => parse_indices(&tokens, cards.len())?,
+    };
+    let choice = CardChoice::new(&indices);
+    check_flip(cards, choice).map(|_| choice)
+}
+
+/// Checks that the leveraged cards flipped are paid for by the non-leveraged
+/// cards flipped.
+pub(crate) fn check_flip(cards: &[Card], choice: CardChoice) -> Result<(), String> {
+    match cards.iter().choose_cards(choice).scalar_value() >= 0 {
+        true => Ok(()),
+        false => Err("you cannot afford to flip these cards".to_string()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        cell::RefCell,
+        io::{Cursor, Write},
+        rc::Rc,
+        sync::{Arc, Mutex},
+    };
+
+    use behaviors::GreedyBehavior;
+    use gemstone::*;
+
+    use super::HumanBehavior;
+
+    /// A writer whose output can be read after the behavior is moved into a
+    /// game, or the session is shared between behaviors.
+    #[derive(Clone, Default)]
+    pub(crate) struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedOutput {
+        pub(crate) fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Plays like the [`GreedyBehavior`] and writes every decision as the
+    /// answers a human would type.
+    struct Recorder {
+        inner: GreedyBehavior,
+        script: Rc<RefCell<String>>,
+    }
+
+    impl Recorder {
+        fn record(&self, line: String) {
+            self.script.borrow_mut().push_str(&format!("{line}\n"));
+        }
+
+        fn indices(cards: &[Card], choice: CardChoice) -> String {
+            (0..cards.len())
+                .filter(|&idx| choice.check(idx))
+                .map(|idx| idx.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+    }
+
+    impl PlayerBehavior for Recorder {
+        fn bid(&mut self, info: &GameInfo) -> BidValue {
+            let bid = self.inner.bid(info);
+            self.record(bid.to_string());
+            bid
+        }
+
+        fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+            let (card, payment) = self.inner.pick_card(info);
+            self.record(card.to_string());
+            self.record(Self::indices(info.my_inventory().as_ref(), payment));
+            (card, payment)
+        }
+
+        fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+            let choice = self.inner.reinvest(info);
+            self.record(Self::indices(info.my_inventory().as_ref(), choice));
+            choice
+        }
+    }
+
+    /// Plays a game between two greedy bots and returns the answers of each
+    /// seat together with the final scores.
+    fn record_game() -> (Vec<String>, GameScores) {
+        let scripts = [Rc::default(), Rc::default()];
+        let mut setup = GameSetup::default();
+        for script in &scripts {
+            setup
+                .insert_player(Recorder {
+                    inner: GreedyBehavior::default(),
+                    script: Rc::clone(script),
+                })
+                .unwrap();
+        }
+        setup.set_deck(Card::gem_deck());
+        let scores = setup.finish().unwrap().run().unwrap();
+        let scripts = scripts.map(|script| script.borrow().clone());
+        (scripts.to_vec(), scores)
+    }
+
+    /// Plays a game between two humans answering from the scripts.
+    fn play_scripted(scripts: &[String]) -> (Result<GameScores>, Vec<SharedOutput>) {
+        let outputs = vec![SharedOutput::default(); scripts.len()];
+        let mut setup = GameSetup::default();
+        for (seat, script) in scripts.iter().enumerate() {
+            let reader = Cursor::new(script.clone().into_bytes());
+            let name = format!("Player {}", seat + 1);
+            setup
+                .insert_player(HumanBehavior::with_io(&name, reader, outputs[seat].clone()))
+                .unwrap();
+        }
+        setup.set_deck(Card::gem_deck());
+        (setup.finish().unwrap().run(), outputs)
+    }
+
+    #[test]
+    fn scripted_game_matches_recorded_game() {
+        let (scripts, expected) = record_game();
+        let (scores, outputs) = play_scripted(&scripts);
+        let scores = scores.unwrap();
+        assert_eq!(scores.get(0), expected.get(0));
+        assert_eq!(scores.get(1), expected.get(1));
+        for output in outputs {
+            let output = output.text();
+            assert!(output.contains("Enter your bid: "));
+            assert!(!output.contains("Invalid input"));
+        }
+    }
+
+    #[test]
+    fn invalid_input_is_asked_again() {
+        let (mut scripts, expected) = record_game();
+        scripts[0].insert_str(0, "abc\n99\n-1\n");
+        let (scores, outputs) = play_scripted(&scripts);
+        assert_eq!(scores.unwrap().get(0), expected.get(0));
+
+        let output = outputs[0].text();
+        assert!(output.contains("Invalid input: `abc` is not a number"));
+        assert_eq!(output.matches("the bid must be between 0 and 6").count(), 2);
+    }
+
+    #[test]
+    fn invalid_card_and_payment_are_asked_again() {
+        let info = "2/!AESEDRR/cfh123;123"
+            .parse::<GemNotation>()
+            .unwrap()
+            .to_info()
+            .unwrap();
+        let reader = Cursor::new(b"9\n0\n30\n0\n1\n".to_vec());
+        let output = SharedOutput::default();
+        let mut human = HumanBehavior::with_io("Player 1", reader, output.clone());
+
+        let (card, payment) = human.pick_card(&info);
+        assert_eq!(card, 0);
+        assert!(payment.check(1) && !payment.check(0));
+
+        let output = output.text();
+        assert!(output.contains("there is no card 9"));
+        assert!(output.contains("there is no card 30"));
+        assert!(output.contains("the payment is worth 1, but you bid 2"));
+    }
+
+    #[test]
+    fn cards_are_chosen_by_code() {
+        let info = "2/!AESEDRR/cfh123;123"
+            .parse::<GemNotation>()
+            .unwrap()
+            .to_info()
+            .unwrap();
+        let reader = Cursor::new(b"byu RR\nbuy ES\nbuy RR pay 1\nbuy RR pay 3\n".to_vec());
+        let output = SharedOutput::default();
+        let mut human = HumanBehavior::with_io("Player 1", reader, output.clone());
+
+        let (card, payment) = human.pick_card(&info);
+        assert_eq!(GemNotation::format_card(info.stack().as_ref()[card]), "RR");
+        assert!(payment.check(2) && !payment.check(0) && !payment.check(1));
+
+        let output = output.text();
+        assert!(output.contains("unknown command `byu`, did you mean `buy`?"));
+        assert!(output.contains("`ES` is not a card code, did you mean `SE`?"));
+        assert!(output.contains("the payment is worth 1, but you bid 2"));
+    }
+
+    #[test]
+    fn flipped_cards_are_resolved_by_leverage() {
+        let info = "-//cf12SE!3AE;123!DRR"
+            .parse::<GemNotation>()
+            .unwrap()
+            .to_info()
+            .unwrap();
+        let reader = Cursor::new(b"flip AE 2\nflip !AE 2\n".to_vec());
+        let output = SharedOutput::default();
+        let mut human = HumanBehavior::with_io("Player 1", reader, output.clone());
+
+        let choice = human.reinvest(&info);
+        let flipped = (0..5).filter(|&idx| choice.check(idx)).collect::<Vec<_>>();
+        assert_eq!(flipped, [1, 4]);
+        assert!(output
+            .text()
+            .contains("there is no non-leveraged `AE`, did you mean `!AE`?"));
+    }
 
-        let choice_indices = input!("Enter cards: " => [usize], []);
-        CardChoice::new(&choice_indices)
+    #[test]
+    fn end_of_input_resigns() {
+        let (mut scripts, _) = record_game();
+        scripts[1].clear();
+        let (scores, outputs) = play_scripted(&scripts);
+        assert!(matches!(scores, Err(GemError::PlayerResigned(1))));
+        assert!(outputs[1].text().contains("Player 2 resigned"));
     }
 }
@@ -1,22 +1,22 @@
+mod card_input;
+mod cli;
 mod human_player;
+mod repl;
+mod tui;
 
-use gemstone::*;
-use human_player::HumanBehavior;
+use std::{env, process::ExitCode};
 
-fn main() -> Result<()> {
-    let mut game_setup = GameSetup::default();
-    game_setup.insert_player(HumanBehavior::new("Alice"))?;
-    game_setup.insert_player(HumanBehavior::new("Charlie"))?;
-    game_setup.shuffle_players();
+use cli::{Command, Failure};
 
-    let mut game = game_setup.finish()?;
-    match game.run() {
-        Ok(scores) => println!("final scores: {scores:?}"),
-        Err(err) => {
-            println!("{}", GemNotation::from_info(game.info_ref()));
-            return Err(err);
+fn main() -> ExitCode {
+    match Command::parse(env::args().skip(1)).and_then(Command::run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("error: {failure}");
+            if let Failure::Usage(_) = failure {
+                eprintln!("run `gemai help` for usage");
+            }
+            failure.exit_code()
         }
     }
-
-    Ok(())
 }
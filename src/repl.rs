@@ -0,0 +1,592 @@
+use std::{
+    cell::RefCell,
+    fs,
+    io::{self, BufRead, BufReader, Stdin, Stdout, Write},
+    rc::Rc,
+};
+
+use gemstone::*;
+
+use crate::{
+    card_input::{did_you_mean, tokenize, KEYWORDS},
+    human_player::{
+        describe_bid, describe_flip, describe_payment, describe_stack, format_codes, parse_bid,
+        parse_flip, parse_payment, parse_pick, BID_PROMPT, CARD_PROMPT, FLIP_PROMPT,
+        PAYMENT_PROMPT,
+    },
+};
+
+const COMMANDS: [(&str, &str); 10] = [
+    ("state", "show the board"),
+    ("scores", "show the scores if the game ended now"),
+    (
+        "majorities",
+        "show the gems of each type held by each player",
+    ),
+    ("history", "list every decision made so far"),
+    ("hint", "suggest a decision"),
+    ("undo", "take back your last decision, in practice games"),
+    (
+        "save <file>",
+        "write every position so far to a record file",
+    ),
+    ("notation", "show the position in gem notation"),
+    ("resign", "resign the game"),
+    ("help", "list the commands"),
+];
+
+/// A decision entered by a human seat.
+#[derive(Clone, Copy, Debug)]
+enum Answer {
+    Bid(BidValue),
+    Pick(usize, CardChoice),
+    Reinvest(CardChoice),
+}
+
+/// A human seat, which plays the answers entered in the [`Repl`].
+struct ReplSeat(Rc<RefCell<Option<Answer>>>);
+
+impl ReplSeat {
+    fn take(&self) -> Answer {
+        self.0
+            .borrow_mut()
+            .take()
+            .expect("the repl answers before stepping the game")
+    }
+}
+
+impl PlayerBehavior for ReplSeat {
+    fn bid(&mut self, _: &GameInfo) -> BidValue {
+        match self.take() {
+            Answer::Bid(bid) => bid,
+            answer => unreachable!("expected a bid, got {answer:?}"),
+        }
+    }
+
+    fn pick_card(&mut self, _: &GameInfo) -> (usize, CardChoice) {
+        match self.take() {
+            Answer::Pick(card, payment) => (card, payment),
+            answer => unreachable!("expected a card, got {answer:?}"),
+        }
+    }
+
+    fn reinvest(&mut self, _: &GameInfo) -> CardChoice {
+        match self.take() {
+            Answer::Reinvest(choice) => choice,
+            answer => unreachable!("expected a reinvestment, got {answer:?}"),
+        }
+    }
+}
+
+/// An interactive shell over a [`Game`] between human and bot seats.
+///
+/// Human seats answer prompts like the [`HumanBehavior`](crate::human_player::HumanBehavior),
+/// but may also enter commands to inspect the game, ask for a hint, or take
+/// back their last decision in practice games.
+pub struct Repl<R = BufReader<Stdin>, W = Stdout> {
+    setup: GameSetup,
+    names: Vec<String>,
+    /// The factory of each bot seat, or `None` for human seats.
+    bots: Vec<Option<BehaviorFactory>>,
+    renderer: BoardRenderer,
+    narrator: Narrator,
+    answer: Rc<RefCell<Option<Answer>>>,
+    practice: bool,
+
+    /// Every position of the game so far, oldest first.
+    positions: Vec<GameInfo>,
+    /// The index of the position before each decision of a human seat.
+    takebacks: Vec<usize>,
+    /// The card picked by the highest bidder, while asking for the payment.
+    pending_card: Option<usize>,
+
+    reader: R,
+    writer: W,
+}
+
+impl Repl {
+    /// Creates a shell reading from and writing to the terminal, drawing the
+    /// board in colour if the terminal supports it.
+    pub fn new() -> Self {
+        let mut repl = Self::with_io(BufReader::new(io::stdin()), io::stdout());
+        repl.renderer = BoardRenderer::for_stream(&io::stdout());
+        repl
+    }
+}
+
+impl<R: BufRead, W: Write> Repl<R, W> {
+    pub fn with_io(reader: R, writer: W) -> Self {
+        Self {
+            setup: GameSetup::default(),
+            names: Vec::new(),
+            bots: Vec::new(),
+            renderer: BoardRenderer::plain(),
+            narrator: Narrator::new(),
+            answer: Rc::default(),
+            practice: false,
+            positions: Vec::new(),
+            takebacks: Vec::new(),
+            pending_card: None,
+            reader,
+            writer,
+        }
+    }
+
+    /// Allows human seats to take back their decisions.
+    pub fn with_practice(mut self, practice: bool) -> Self {
+        self.practice = practice;
+        self
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.setup.set_seed(seed);
+    }
+
+    /// Adds a seat answered from the input of the shell.
+    pub fn add_human(&mut self, name: &str) -> Result<()> {
+        self.setup.insert_player(ReplSeat(self.answer.clone()))?;
+        self.names.push(name.to_string());
+        self.bots.push(None);
+        Ok(())
+    }
+
+    /// Adds a seat played by the behaviors of `factory`, which creates the
+    /// bot again whenever a decision is taken back, such that stateful bots
+    /// forget what was taken back.
+    pub fn add_bot(
+        &mut self,
+        name: &str,
+        factory: impl Fn() -> Box<dyn PlayerBehavior> + 'static,
+    ) -> Result<()> {
+        self.setup.insert_player(factory())?;
+        self.names.push(name.to_string());
+        self.bots.push(Some(Box::new(factory)));
+        Ok(())
+    }
+
+    /// Returns every position of the game so far, oldest first.
+    pub fn positions(&self) -> &[GameInfo] {
+        &self.positions
+    }
+
+    /// Plays the game until it ends, returning the final scores, or `None` if
+    /// a human seat resigned or the input ended. This function will return an
+    /// error if the game ends with an error.
+    pub fn run(&mut self) -> Result<Option<GameScores>> {
+        let mut game = std::mem::take(&mut self.setup).finish()?;
+        self.renderer = std::mem::take(&mut self.renderer).with_names(self.names.clone());
+        self.narrator = Narrator::new().with_names(self.names.clone());
+        self.positions = vec![game.info_ref().clone()];
+        for sentence in self.narrator.narrate_start(game.info_ref()) {
+            self.say(sentence);
+        }
+        let mut described = false;
+
+        while let Some((player, decision)) = game.info_ref().next_decision() {
+            if self.bots[player].is_some() {
+                game.step()?;
+                self.record(&game);
+                continue;
+            }
+
+            let view = Self::view(game.info_ref(), player);
+            if !described {
+                self.say(format!("\n{} ====================", self.names[player]));
+                self.say(self.describe(&view, decision));
+                described = true;
+            }
+            let line = match self.read_line(Self::prompt(decision, self.pending_card)) {
+                Some(line) => line,
+                None => return Ok(self.resign(player)),
+            };
+            let tokens = tokenize(&line);
+            let command = tokens.first().map(|word| word.to_lowercase());
+            match command.as_deref() {
+                Some("resign" | "quit") => return Ok(self.resign(player)),
+                Some("undo") => {
+                    if self.undo(&mut game) {
+                        described = false;
+                    }
+                    continue;
+                }
+                Some(command) if self.command(command, &tokens[1..], &view, decision) => continue,
+                _ => {}
+            }
+
+            match self.answer(&line, &view, decision) {
+                Ok(Some(answer)) => {
+                    self.takebacks.push(self.positions.len() - 1);
+                    *self.answer.borrow_mut() = Some(answer);
+                    game.step()?;
+                    self.record(&game);
+                    described = false;
+                }
+                Ok(None) => self.say(describe_payment(&view)),
+                Err(reason) => self.say(format!("Invalid input: {reason}. Please try again.")),
+            }
+        }
+
+        let scores = game.info_ref().scores();
+        self.say("\nThe game is over.");
+        self.show_scores(game.info_ref());
+        Ok(Some(scores))
+    }
+
+    /// Returns the position as seen by the player making the next decision.
+    fn view(info: &GameInfo, player: usize) -> GameInfo {
+        let mut view = info.clone();
+        view.set_current_player(player);
+        view
+    }
+
+    fn describe(&self, view: &GameInfo, decision: Decision) -> String {
+        let board = self.renderer.render(view);
+        let question = match (decision, self.pending_card) {
+            (Decision::Bid, _) => describe_bid(view),
+            (Decision::PickCard, None) => describe_stack(view),
+            (Decision::PickCard, Some(_)) => describe_payment(view),
+            (Decision::Reinvest, _) => describe_flip(view),
+        };
+        format!("{board}\n\n{question}")
+    }
+
+    fn prompt(decision: Decision, pending_card: Option<usize>) -> &'static str {
+        match (decision, pending_card) {
+            (Decision::Bid, _) => BID_PROMPT,
+            (Decision::PickCard, None) => CARD_PROMPT,
+            (Decision::PickCard, Some(_)) => PAYMENT_PROMPT,
+            (Decision::Reinvest, _) => FLIP_PROMPT,
+        }
+    }
+
+    /// Parses the answer to the current question. Returns `None` if a card
+    /// was picked without a payment, which is asked for next.
+    fn answer(
+        &mut self,
+        line: &str,
+        view: &GameInfo,
+        decision: Decision,
+    ) -> std::result::Result<Option<Answer>, String> {
+        let capital = view.my_inventory().iter().capital();
+        Ok(Some(match (decision, self.pending_card) {
+            (Decision::Bid, _) => Answer::Bid(parse_bid(line, capital)?),
+            (Decision::PickCard, None) => match parse_pick(line, view)? {
+                (card, Some(payment)) => Answer::Pick(card, payment),
+                (card, None) => {
+                    self.pending_card = Some(card);
+                    return Ok(None);
+                }
+            },
+            (Decision::PickCard, Some(card)) => {
+                let payment = parse_payment(line, view)?;
+                self.pending_card = None;
+                Answer::Pick(card, payment)
+            }
+            (Decision::Reinvest, _) => Answer::Reinvest(parse_flip(line, view)?),
+        }))
+    }
+
+    /// Adds the position after a step, and narrates the step.
+    fn record(&mut self, game: &Game) {
+        let before = self
+            .positions
+            .last()
+            .expect("the first position is recorded");
+        for sentence in self.narrator.narrate(before, game.info_ref()) {
+            self.say(sentence);
+        }
+        self.positions.push(game.info_ref().clone());
+    }
+
+    fn resign(&mut self, player: usize) -> Option<GameScores> {
+        self.say(format!("\n{} resigned", self.names[player]));
+        None
+    }
+
+    /// Takes back the last decision of a human seat, and every decision of
+    /// the bots after it, or only cancels the card picked while asking for
+    /// the payment. Returns whether anything was taken back.
+    fn undo(&mut self, game: &mut Game) -> bool {
+        if self.pending_card.take().is_some() {
+            self.say("Cancelled the card pick.");
+            return true;
+        }
+        if !self.practice {
+            self.say("Decisions can only be taken back in practice games.");
+            return false;
+        }
+        let idx = match self.takebacks.pop() {
+            Some(idx) => idx,
+            None => {
+                self.say("There is no decision to take back.");
+                return false;
+            }
+        };
+        self.positions.truncate(idx + 1);
+        let behaviors = self
+            .bots
+            .iter()
+            .map(|bot| match bot {
+                Some(factory) => factory(),
+                None => Box::new(ReplSeat(self.answer.clone())) as Box<dyn PlayerBehavior>,
+            })
+            .collect();
+        *game = Game::new(behaviors);
+        game.restore(self.positions[idx].clone());
+        self.say("Took back the last decision.");
+        true
+    }
+
+    fn say(&mut self, line: impl AsRef<str>) {
+        let _ = writeln!(self.writer, "{}", line.as_ref());
+    }
+
+    /// Reads a trimmed line, or returns `None` once the input ends.
+    fn read_line(&mut self, prompt: &str) -> Option<String> {
+        let _ = write!(self.writer, "{prompt}");
+        let _ = self.writer.flush();
+        let mut buf = String::new();
+        match self.reader.read_line(&mut buf) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(buf.trim().to_string()),
+        }
+    }
+}
+
+//
+// Commands
+//
+
+impl<R: BufRead, W: Write> Repl<R, W> {
+    /// Runs an inspection command. Returns `false` if the input is not a
+    /// command, such that it is parsed as an answer instead.
+    fn command(
+        &mut self,
+        command: &str,
+        args: &[&str],
+        view: &GameInfo,
+        decision: Decision,
+    ) -> bool {
+        match command {
+            "state" => self.say(self.renderer.render(view)),
+            "scores" => self.show_scores(view),
+            "majorities" => self.show_majorities(view),
+            "history" => self.show_history(view),
+            "hint" => self.say(Self::hint(view, decision, self.pending_card)),
+            "notation" => self.say(GemNotation::from_info(view).to_string()),
+            "save" => match args {
+                [path] => match self.save(path) {
+                    Ok(()) => self.say(format!(
+                        "Saved {} positions to {path}.",
+                        self.positions.len()
+                    )),
+                    Err(err) => self.say(format!("Could not save to {path}: {err}")),
+                },
+                _ => self.say("Usage: save <file>"),
+            },
+            "help" => {
+                for (command, description) in COMMANDS {
+                    self.say(format!("  {command:<12}{description}"));
+                }
+                self.say("Anything else answers the question.");
+            }
+            _ => {
+                // keywords start an answer rather than a misspelled command
+                let names = COMMANDS
+                    .iter()
+                    .filter_map(|(command, _)| command.split(' ').next());
+                let suggestion = did_you_mean(command, names);
+                if !KEYWORDS.contains(&command) && !suggestion.is_empty() {
+                    self.say(format!("Unknown command `{command}`{suggestion}"));
+                    return true;
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    fn show_scores(&mut self, info: &GameInfo) {
+        let scores = info.scores();
+        for player in 0..self.names.len() {
+            self.say(format!("  {}: {}", self.names[player], scores.get(player)));
+        }
+    }
+
+    fn show_majorities(&mut self, info: &GameInfo) {
+        for gem in GemType::iter() {
+            let counts = (0..self.names.len())
+                .map(|player| {
+                    let count = info.majorities().count(player, gem);
+                    format!(
+                        "{} {}/{}",
+                        self.names[player],
+                        count.non_leveraged,
+                        count.total()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let holder = match info.majorities().majority(gem) {
+                Majority::Nobody => "nobody".to_string(),
+                Majority::Owned(player) => self.names[player].clone(),
+                Majority::Shared(players) => players
+                    .iter()
+                    .map(|&player| self.names[player].as_str())
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+            };
+            self.say(format!("  {gem:?}: {counts}, held by {holder}"));
+        }
+    }
+
+    fn show_history(&mut self, info: &GameInfo) {
+        if info.history().is_empty() {
+            self.say("No decisions have been made yet.");
+        }
+        let lines = info
+            .history()
+            .iter()
+            .enumerate()
+            .map(|(idx, event)| {
+                format!(
+                    "{:>3}. {}: {}",
+                    idx + 1,
+                    self.names[event.player()],
+                    Self::format_event(event)
+                )
+            })
+            .collect::<Vec<_>>();
+        for line in lines {
+            self.say(line);
+        }
+    }
+
+    fn format_event(event: &GameEvent) -> String {
+        let codes = |cards: &[Card]| {
+            let codes = format_codes(cards, CardChoice::ALL);
+            match codes.is_empty() {
+                true => "nothing".to_string(),
+                false => codes,
+            }
+        };
+        match event {
+            GameEvent::Bid { bid, highest, .. } if bid > highest => format!("bid {bid}"),
+            GameEvent::Bid { .. } => "passed".to_string(),
+            GameEvent::Purchase {
+                card,
+                price,
+                payment,
+                ..
+            } => format!(
+                "bought {} for {price}, paying with {}",
+                GemNotation::format_card(*card),
+                codes(payment)
+            ),
+            GameEvent::Reinvestment { flipped, .. } => format!("flipped {}", codes(flipped)),
+        }
+    }
+
+    fn hint(view: &GameInfo, decision: Decision, pending_card: Option<usize>) -> String {
+        let player = view.current_player();
+        let inventory = view.my_inventory();
+        match (decision, pending_card) {
+            (Decision::Bid, _) => {
+                let valuation = BidValuation::new(view);
+                let max_bid = valuation.max_bid(player);
+                match (valuation.best_card(player), max_bid > view.highest_bid()) {
+                    (Some(card), true) => format!(
+                        "Bid up to {max_bid} for {}.",
+                        GemNotation::format_card(view.stack().as_ref()[card])
+                    ),
+                    _ => "Pass by bidding 0.".to_string(),
+                }
+            }
+            (Decision::PickCard, pending_card) => {
+                let card = pending_card
+                    .or_else(|| BidValuation::new(view).best_card(player))
+                    .unwrap_or(0);
+                let cost = ProtectMajorities::from_info(view, player);
+                let payment = PaymentOptimizer::new(inventory)
+                    .best(view.highest_bid(), &cost)
+                    .map(|payment| format_codes(inventory, payment.choice))
+                    .unwrap_or_default();
+                format!(
+                    "buy {} pay {payment}",
+                    GemNotation::format_card(view.stack().as_ref()[card])
+                )
+            }
+            (Decision::Reinvest, _) => {
+                let plan = ReinvestmentPlanner::new(view, player).best();
+                format!(
+                    "flip {} ({})",
+                    format_codes(inventory, plan.choice),
+                    plan.explanation
+                )
+            }
+        }
+    }
+
+    /// Writes every position so far as a transcript, as read by the `replay`
+    /// and `check` commands.
+    fn save(&self, path: &str) -> io::Result<()> {
+        let mut transcript = Transcript::new();
+        for info in &self.positions {
+            transcript.push_position(info);
+        }
+        fs::write(path, transcript.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use behaviors::GreedyBehavior;
+
+    use super::Repl;
+
+    fn play(input: &str, practice: bool) -> (Repl<Cursor<String>, Vec<u8>>, String) {
+        let mut repl =
+            Repl::with_io(Cursor::new(input.to_string()), Vec::new()).with_practice(practice);
+        repl.set_seed(3);
+        repl.add_human("Player 1").unwrap();
+        repl.add_bot("greedy", || Box::new(GreedyBehavior::default()))
+            .unwrap();
+        assert!(repl.run().unwrap().is_none());
+        let output = String::from_utf8(repl.writer.clone()).unwrap();
+        (repl, output)
+    }
+
+    #[test]
+    fn commands_do_not_answer() {
+        let (repl, output) = play("state\nscores\nhistory\nhint\nnotation\nhitn\n", false);
+        assert_eq!(repl.positions().len(), 1);
+        assert!(output.contains("Round 1 of 6, auction phase"));
+        assert!(output.contains("Unknown command `hitn`, did you mean `hint`?"));
+    }
+
+    #[test]
+    fn undo_restores_the_position_in_practice_games() {
+        let (repl, output) = play("0\nundo\nundo\n", true);
+        assert_eq!(repl.positions().len(), 1);
+        assert!(output.contains("Took back the last decision."));
+        assert!(output.contains("There is no decision to take back."));
+
+        let (repl, output) = play("0\nundo\n", false);
+        assert!(repl.positions().len() > 1);
+        assert!(output.contains("only be taken back in practice games"));
+    }
+
+    #[test]
+    fn undo_only_cancels_a_pending_card_pick() {
+        let (repl, output) = play("6\n1\nundo\n", true);
+        let positions = repl.positions().len();
+        assert!(positions > 1);
+        assert!(output.contains("Cancelled the card pick."));
+        assert!(!output.contains("Took back the last decision."));
+
+        let (repl, _) = play("6\n1\nundo\nundo\n", true);
+        assert!(repl.positions().len() < positions);
+    }
+}
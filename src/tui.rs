@@ -0,0 +1,487 @@
+use std::{
+    io::{self, Read, Write},
+    process::{Command, Stdio},
+    result::Result,
+    sync::{Arc, Mutex},
+};
+
+use gemstone::*;
+
+use crate::human_player::{check_flip, check_payment};
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const ENTER_SCREEN: &str = "\x1b[?1049h\x1b[?25l";
+const LEAVE_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+const REVERSE: &str = "\x1b[7m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// A key pressed in the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Left,
+    Right,
+    Enter,
+    Space,
+    Backspace,
+    Char(char),
+}
+
+/// The terminal in raw mode on the alternate screen, restored when dropped.
+struct RawTerminal {
+    saved: String,
+}
+
+impl RawTerminal {
+    fn enable() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        let mut stdout = io::stdout();
+        write!(stdout, "{ENTER_SCREEN}")?;
+        stdout.flush()?;
+        Ok(Self {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "{LEAVE_SCREEN}");
+        let _ = stdout.flush();
+    }
+}
+
+/// Runs `stty` on the terminal of stdin, returning its output.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => Err(io::Error::other(format!(
+            "`stty {}` failed",
+            args.join(" ")
+        ))),
+    }
+}
+
+/// The screen and keyboard shared by every [`TuiBehavior`] at the table, such
+/// that players taking turns on a single terminal see a privacy screen
+/// before their decisions.
+pub struct TuiSession {
+    input: Box<dyn Read + Send>,
+    output: Box<dyn Write + Send>,
+    /// Whether the terminal is still to be switched to raw mode.
+    raw: bool,
+    terminal: Option<RawTerminal>,
+    names: Vec<Option<String>>,
+    last_seat: Option<usize>,
+}
+
+impl TuiSession {
+    /// Creates a session on the terminal, which is switched to raw mode on
+    /// the first decision and restored once every behavior is dropped.
+    pub fn new() -> Arc<Mutex<Self>> {
+        let mut session = Self::with_io(io::stdin(), io::stdout());
+        session.raw = true;
+        Arc::new(Mutex::new(session))
+    }
+
+    /// Creates a session reading keys from `input` and drawing to `output`
+    /// without changing the mode of the terminal.
+    pub fn with_io(input: impl Read + Send + 'static, output: impl Write + Send + 'static) -> Self {
+        Self {
+            input: Box::new(input),
+            output: Box::new(output),
+            raw: false,
+            terminal: None,
+            names: Vec::new(),
+            last_seat: None,
+        }
+    }
+
+    /// Reads a single key, or returns `None` once the input ends. Unknown
+    /// escape sequences are skipped.
+    fn read_key(&mut self) -> Option<Key> {
+        loop {
+            let key = match self.read_byte()? {
+                b'\r' | b'\n' => Key::Enter,
+                b' ' => Key::Space,
+                0x7f | 0x08 => Key::Backspace,
+                // ctrl-c and ctrl-d resign like `q`
+                0x03 | 0x04 => Key::Char('q'),
+                0x1b => match (self.read_byte()?, self.read_byte()?) {
+                    (b'[', b'C') => Key::Right,
+                    (b'[', b'D') => Key::Left,
+                    _ => continue,
+                },
+                byte if byte.is_ascii_graphic() => Key::Char(byte.to_ascii_lowercase() as char),
+                _ => continue,
+            };
+            return Some(key);
+        }
+    }
+
+    fn read_byte(&mut self) -> Option<u8> {
+        let mut byte = [0];
+        match self.input.read(&mut byte) {
+            Ok(1) => Some(byte[0]),
+            _ => None,
+        }
+    }
+
+    /// Clears the screen and draws the lines.
+    fn draw(&mut self, lines: &[String]) {
+        // a terminal which cannot be switched is not tried again on every
+        // redraw
+        if std::mem::take(&mut self.raw) {
+            self.terminal = RawTerminal::enable().ok();
+        }
+        // raw mode does not return the carriage on a new line
+        let _ = write!(self.output, "{CLEAR}{}\r\n", lines.join("\r\n"));
+        let _ = self.output.flush();
+    }
+
+    /// Shows a privacy screen if another seat used the terminal last, and
+    /// waits for the player to take over. Returns `false` if they resigned.
+    fn take_seat(&mut self, seat: usize, name: &str) -> bool {
+        if self.names.len() <= seat {
+            self.names.resize(seat + 1, None);
+        }
+        self.names[seat] = Some(name.to_string());
+        let previous = self.last_seat.replace(seat);
+        if previous.is_none() || previous == Some(seat) {
+            return true;
+        }
+        self.draw(&[
+            format!("Pass the keyboard to {name}."),
+            String::new(),
+            "Press enter when ready, or q to resign.".to_string(),
+        ]);
+        loop {
+            match self.read_key() {
+                Some(Key::Enter) => return true,
+                Some(Key::Char('q')) | None => return false,
+                _ => {}
+            }
+        }
+    }
+
+    fn renderer(&self) -> BoardRenderer {
+        let names = self.names.iter().enumerate().map(|(seat, name)| {
+            name.clone()
+                .unwrap_or_else(|| format!("Player {}", seat + 1))
+        });
+        BoardRenderer::ansi().with_names(names)
+    }
+}
+
+/// A behavior asking a human for every decision on a full-screen terminal
+/// interface, where cards are selected with the keyboard.
+///
+/// Every seat playing on the same terminal shares a [`TuiSession`]. Once the
+/// input ends or `q` is pressed, the player resigns and the game ends with
+/// [`GemError::PlayerResigned`].
+pub struct TuiBehavior {
+    name: String,
+    session: Arc<Mutex<TuiSession>>,
+    resigned: bool,
+}
+
+impl TuiBehavior {
+    pub fn new(name: &str, session: Arc<Mutex<TuiSession>>) -> Self {
+        Self {
+            name: name.to_string(),
+            session,
+            resigned: false,
+        }
+    }
+
+    /// Redraws the screen with the board above the question and the current
+    /// selection.
+    fn screen(&self, session: &mut TuiSession, info: &GameInfo, question: &[String]) {
+        let mut lines = vec![format!("{REVERSE} {} {RESET}", self.name), String::new()];
+        lines.extend(session.renderer().render(info).lines().map(str::to_string));
+        lines.push(String::new());
+        lines.extend(question.iter().cloned());
+        session.draw(&lines);
+    }
+
+    /// Lets the player choose cards with the arrow keys and space, checking
+    /// the choice as it changes. Returns `None` if the player resigned, and
+    /// `Some(None)` if they went back.
+    fn select(
+        &mut self,
+        session: &mut TuiSession,
+        info: &GameInfo,
+        question: &str,
+        check: impl Fn(CardChoice) -> Result<(), String>,
+    ) -> Option<Option<CardChoice>> {
+        let cards = info.my_inventory().as_ref();
+        let mut selected = vec![false; cards.len()];
+        let mut cursor = 0;
+        loop {
+            let indices = (0..cards.len())
+                .filter(|&idx| selected[idx])
+                .collect::<Vec<_>>();
+            let choice = CardChoice::new(&indices);
+            let status = match check(choice) {
+                Ok(()) => format!("{GREEN}press enter to confirm{RESET}"),
+                Err(reason) => format!("{RED}{reason}{RESET}"),
+            };
+            let lines = [
+                question.to_string(),
+                Self::card_row(cards, cursor, &selected),
+                status,
+                "left/right move, space toggles, enter confirms, b goes back, q resigns"
+                    .to_string(),
+            ];
+            self.screen(session, info, &lines);
+
+            match session.read_key() {
+                Some(Key::Left) => cursor = cursor.saturating_sub(1),
+                Some(Key::Right) => cursor = (cursor + 1).min(cards.len().saturating_sub(1)),
+                Some(Key::Space) if cursor < cards.len() => selected[cursor] = !selected[cursor],
+                Some(Key::Enter) if check(choice).is_ok() => return Some(Some(choice)),
+                Some(Key::Char('b')) => return Some(None),
+                Some(Key::Char('q')) | None => return None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Lets the player choose a card of the stack. Returns `None` if the
+    /// player resigned.
+    fn select_card(&mut self, session: &mut TuiSession, info: &GameInfo) -> Option<usize> {
+        let stack = info.stack().as_ref();
+        let mut cursor = 0;
+        loop {
+            let lines = [
+                format!("You bid {}. Choose a card:", info.highest_bid()),
+                Self::card_row(stack, cursor, &[]),
+                String::new(),
+                "left/right move, enter chooses, q resigns".to_string(),
+            ];
+            self.screen(session, info, &lines);
+
+            match session.read_key() {
+                Some(Key::Left) => cursor = cursor.saturating_sub(1),
+                Some(Key::Right) => cursor = (cursor + 1).min(stack.len().saturating_sub(1)),
+                Some(Key::Enter) => return Some(cursor),
+                Some(Key::Char('q')) | None => return None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Writes the cards on a single line, with the card under the cursor in
+    /// reverse video and selected cards marked by `+`.
+    fn card_row(cards: &[Card], cursor: usize, selected: &[bool]) -> String {
+        cards
+            .iter()
+            .enumerate()
+            .map(|(idx, &card)| {
+                let leverage = if card.is_leveraged() { "!" } else { "" };
+                let mark = if selected.get(idx) == Some(&true) {
+                    "+"
+                } else {
+                    " "
+                };
+                let cell = format!("{mark}{leverage}{}", GemNotation::format_card(card));
+                match idx == cursor {
+                    true => format!("{REVERSE}{cell}{RESET}"),
+                    false => cell,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
+
+impl PlayerBehavior for TuiBehavior {
+    fn bid(&mut self, info: &GameInfo) -> BidValue {
+        let session = self.session.clone();
+        let mut session = session.lock().unwrap();
+        if !session.take_seat(info.current_player(), &self.name) {
+            self.resigned = true;
+            return 0;
+        }
+
+        let capital = info.my_inventory().iter().capital();
+        let highest = info.highest_bid();
+        let mut bid = (highest + 1).clamp(0, capital);
+        let mut typed = false;
+        loop {
+            let status = match bid > highest {
+                true => format!("{GREEN}bid {bid}{RESET}"),
+                false => format!("{RED}a bid of {bid} passes{RESET}"),
+            };
+            let lines = [
+                format!("The highest bid is {highest}. Your capital is {capital}."),
+                format!("Your bid: {REVERSE} {bid} {RESET}"),
+                status,
+                "left/right or digits change the bid, p passes, enter confirms, q resigns"
+                    .to_string(),
+            ];
+            self.screen(&mut session, info, &lines);
+
+            match session.read_key() {
+                Some(Key::Left) => bid = (bid - 1).max(0),
+                Some(Key::Right) => bid = (bid + 1).min(capital),
+                Some(Key::Char(digit @ '0'..='9')) => {
+                    let digit = digit as BidValue - '0' as BidValue;
+                    // a digit which makes the bid unaffordable starts a new bid
+                    bid = match bid.checked_mul(10).and_then(|bid| bid.checked_add(digit)) {
+                        Some(appended) if typed && appended <= capital => appended,
+                        _ => digit.min(capital),
+                    };
+                    typed = true;
+                }
+                Some(Key::Backspace) => bid /= 10,
+                Some(Key::Char('p')) => return 0,
+                Some(Key::Enter) => return bid,
+                Some(Key::Char('q')) | None => {
+                    self.resigned = true;
+                    return 0;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn pick_card(&mut self, info: &GameInfo) -> (usize, CardChoice) {
+        let session = self.session.clone();
+        let mut session = session.lock().unwrap();
+        if !session.take_seat(info.current_player(), &self.name) {
+            self.resigned = true;
+            return (0, CardChoice::NONE);
+        }
+
+        let price = info.highest_bid();
+        let cards = info.my_inventory().as_ref();
+        while let Some(card) = self.select_card(&mut session, info) {
+            let question = format!(
+                "Pay {price} for {}:",
+                GemNotation::format_card(info.stack().as_ref()[card])
+            );
+            match self.select(&mut session, info, &question, |choice| {
+                check_payment(cards, choice, price)
+            }) {
+                Some(Some(payment)) => return (card, payment),
+                Some(None) => continue,
+                None => break,
+            }
+        }
+        self.resigned = true;
+        (0, CardChoice::NONE)
+    }
+
+    fn reinvest(&mut self, info: &GameInfo) -> CardChoice {
+        let session = self.session.clone();
+        let mut session = session.lock().unwrap();
+        if !session.take_seat(info.current_player(), &self.name) {
+            self.resigned = true;
+            return CardChoice::NONE;
+        }
+
+        let cards = info.my_inventory().as_ref();
+        let question = "Choose cards to flip, paying for leveraged cards with coins:";
+        loop {
+            match self.select(&mut session, info, question, |choice| {
+                check_flip(cards, choice)
+            }) {
+                Some(Some(choice)) => return choice,
+                Some(None) => continue,
+                None => {
+                    self.resigned = true;
+                    return CardChoice::NONE;
+                }
+            }
+        }
+    }
+
+    fn resigned(&self) -> bool {
+        self.resigned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Cursor,
+        sync::{Arc, Mutex},
+    };
+
+    use gemstone::*;
+
+    use super::{TuiBehavior, TuiSession};
+    use crate::human_player::tests::SharedOutput;
+
+    const RIGHT: &str = "\x1b[C";
+
+    fn session(keys: &str) -> (Arc<Mutex<TuiSession>>, SharedOutput) {
+        let output = SharedOutput::default();
+        let session = TuiSession::with_io(Cursor::new(keys.as_bytes().to_vec()), output.clone());
+        (Arc::new(Mutex::new(session)), output)
+    }
+
+    fn position(notation: &str) -> GameInfo {
+        notation.parse::<GemNotation>().unwrap().to_info().unwrap()
+    }
+
+    #[test]
+    fn bid_is_typed_or_stepped() {
+        let info = position("2/!AESEDRR/cfh123;123");
+        let (session, _) = session(&format!("4\r5{RIGHT}\rp"));
+        let mut behavior = TuiBehavior::new("Ann", session);
+        assert_eq!(behavior.bid(&info), 4);
+        assert_eq!(behavior.bid(&info), 6);
+        assert_eq!(behavior.bid(&info), 0);
+        assert!(!behavior.resigned());
+    }
+
+    #[test]
+    fn multi_digit_bids_stay_within_the_capital() {
+        // a capital of 16 allows two digits, but never three
+        let info = position("-/SEDRR/cf123AEASTT;123;123");
+        let (session, _) = session("16\r145\r999\r");
+        let mut behavior = TuiBehavior::new("Ann", session);
+        assert_eq!(behavior.bid(&info), 16);
+        assert_eq!(behavior.bid(&info), 5);
+        assert_eq!(behavior.bid(&info), 9);
+    }
+
+    #[test]
+    fn payment_must_cover_the_bid() {
+        let info = position("2/!AESEDRR/cfh123;123");
+        let (session, output) = session(&format!("{RIGHT}\r \r{RIGHT} \r"));
+        let mut behavior = TuiBehavior::new("Ann", session);
+        let (card, payment) = behavior.pick_card(&info);
+        assert_eq!(card, 1);
+        assert!(payment.check(0) && payment.check(1) && !payment.check(2));
+        assert!(output
+            .text()
+            .contains("the payment is worth 1, but you bid 2"));
+    }
+
+    #[test]
+    fn privacy_screen_between_seats() {
+        let (session, output) = session("0\r\r0\rq");
+        let mut ann = TuiBehavior::new("Ann", session.clone());
+        let mut bob = TuiBehavior::new("Bob", session);
+        ann.bid(&position("2/!AESEDRR/cfh123;123"));
+        bob.bid(&position("2/!AESEDRR/fh123;c123"));
+        assert!(output.text().contains("Pass the keyboard to Bob."));
+        assert!(!bob.resigned());
+
+        ann.bid(&position("2/!AESEDRR/cfh123;123"));
+        assert!(output.text().contains("Pass the keyboard to Ann."));
+        assert!(ann.resigned());
+    }
+}